@@ -53,6 +53,8 @@ macro_rules! asset_enum {
 asset_enum! {
     #[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
     pub enum AsepriteKey {
+        Bee => "aseprite/bee.aseprite",
+        Beetle => "aseprite/beetle.aseprite",
         Bluberry => "aseprite/bluberry.aseprite",
         Hampter => "aseprite/hampter.aseprite",
         HamsterAnimation => "aseprite/hamster_animation.aseprite",
@@ -76,10 +78,16 @@ asset_enum! {
     pub enum SfxKey {
         ButtonHover => "audio/sfx/button_hover.ogg",
         ButtonPress => "audio/sfx/button_press.ogg",
+        Rolling => "audio/sfx/rolling.wav",
         Step1 => "audio/sfx/step1.ogg",
         Step2 => "audio/sfx/step2.ogg",
         Step3 => "audio/sfx/step3.ogg",
         Step4 => "audio/sfx/step4.ogg",
+        Break => "audio/sfx/break.wav",
+        // level sounds reusing UI sounds, with their own limits
+        Door => "audio/sfx/button_press.ogg",
+        Dock => "audio/sfx/button_press.ogg",
+        Pickup => "audio/sfx/button_hover.ogg",
     }
     asset_type: AudioSource,
 }
@@ -89,6 +97,9 @@ asset_enum! {
     pub enum SoundtrackKey {
         Credits => "audio/soundtracks/monkeys_spinning_monkeys.ogg",
         Gameplay => "audio/soundtracks/fluffing_a_duck.ogg",
+        GameplayPowerUp => "audio/soundtracks/fluffing_a_duck_power_up.wav",
+        GameplayTension => "audio/soundtracks/fluffing_a_duck_tension.wav",
+        LevelComplete => "audio/soundtracks/level_complete.wav",
     }
     asset_type: AudioSource,
 }
@@ -115,4 +126,13 @@ impl<K: AssetKey> HandleMap<K> {
         self.values()
             .all(|x| asset_server.is_loaded_with_dependencies(x))
     }
+
+    /// How many of the assets are loaded, out of how many.
+    pub fn loaded_count(&self, asset_server: &AssetServer) -> (usize, usize) {
+        let loaded = self
+            .values()
+            .filter(|x| asset_server.is_loaded_with_dependencies(*x))
+            .count();
+        (loaded, self.len())
+    }
 }
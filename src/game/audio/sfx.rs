@@ -1,36 +1,223 @@
-use bevy::{audio::PlaybackMode, prelude::*};
-use rand::seq::SliceRandom;
+use std::time::Duration;
 
-use crate::game::assets::{HandleMap, SfxKey};
+use bevy::{
+    audio::{PlaybackMode, SpatialScale, Volume},
+    prelude::*,
+    utils::HashMap,
+};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    game::assets::{HandleMap, SfxKey},
+    screen::{Pause, Screen},
+    settings::AudioSettings,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<IsSfx>();
+    app.register_type::<FollowEmitter>();
+    app.init_resource::<SfxLimits>();
     app.observe(play_sfx);
+    app.add_systems(
+        Update,
+        (
+            update_sfx_volume.run_if(resource_changed::<AudioSettings>),
+            follow_emitters,
+        ),
+    );
+    app.add_systems(OnExit(Pause::Running), pause_game_sounds);
+    app.add_systems(OnEnter(Pause::Running), resume_game_sounds);
+    // the pause state is gone when quitting to the title, without entering `Running` again
+    app.add_systems(OnExit(Screen::Playing), resume_game_sounds);
 }
 
+/// Pixels per unit of distance for positional sounds,
+/// they get quieter with the square of the distance beyond one unit.
+const SPATIAL_UNIT: f32 = 120.;
+
+/// Random pitch and volume change of every footstep, so they don't sound the same.
+const STEP_VARIANCE: f32 = 0.1;
+
 fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
+    time: Res<Time<Real>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    audio_settings: Res<AudioSettings>,
+    limits: Res<SfxLimits>,
+    playing: Query<(Entity, &IsSfx)>,
+    emitters: Query<&GlobalTransform>,
+    mut last_played: Local<HashMap<SfxKey, Duration>>,
 ) {
-    let sfx_key = match trigger.event() {
-        PlaySfx::Key(key) => *key,
-        PlaySfx::RandomStep => random_step(),
+    let PlaySfx { sfx, position } = *trigger.event();
+    let (sfx_key, speed, pitch_variance, volume_variance) = match sfx {
+        Sfx::Key(key) => (key, 1.0, 0.0, 0.0),
+        Sfx::Pitched(key, speed) => (key, speed, 0.0, 0.0),
+        Sfx::Varied { key, pitch, volume } => (key, 1.0, pitch, volume),
+        Sfx::RandomStep => (random_step(), 1.0, STEP_VARIANCE, STEP_VARIANCE),
     };
-    commands.spawn(AudioSourceBundle {
-        source: sfx_handles[&sfx_key].clone_weak(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
+    // untargeted triggers target `Entity::PLACEHOLDER`, which has no transform
+    let emitter = emitters
+        .get(trigger.entity())
+        .ok()
+        .map(|transform| (trigger.entity(), transform.translation().truncate()));
+
+    let now = time.elapsed();
+    if let Some(limit) = limits.0.get(&sfx_key) {
+        if last_played
+            .get(&sfx_key)
+            .is_some_and(|last| now.saturating_sub(*last) < limit.cooldown)
+        {
+            return;
+        }
+
+        let mut instances: Vec<(Entity, &IsSfx)> = playing
+            .iter()
+            .filter(|(_, sfx)| sfx.key == sfx_key)
+            .collect();
+        if instances.len() >= limit.max_concurrent {
+            if !limit.replace_oldest {
+                return;
+            }
+            instances.sort_by_key(|(_, sfx)| sfx.started);
+            let excess = instances.len() + 1 - limit.max_concurrent.max(1);
+            for (entity, _) in instances.into_iter().take(excess) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+    last_played.insert(sfx_key, now);
+
+    let mut rng = rand::thread_rng();
+    let speed = speed * (1. + rng.gen_range(-1.0..=1.0) * pitch_variance);
+    let gain = (1. + rng.gen_range(-1.0..=1.0) * volume_variance).max(0.);
+    let mut sound = commands.spawn((
+        AudioSourceBundle {
+            source: sfx_handles[&sfx_key].clone_weak(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                speed,
+                volume: Volume::new(audio_settings.sfx_volume() * gain),
+                spatial: position.is_some() || emitter.is_some(),
+                spatial_scale: Some(SpatialScale::new_2d(1. / SPATIAL_UNIT)),
+                ..default()
+            },
         },
-    });
+        IsSfx {
+            key: sfx_key,
+            gain,
+            started: now,
+        },
+    ));
+    if let Some(position) = position {
+        sound.insert(TransformBundle::from_transform(
+            Transform::from_translation(position.extend(0.)),
+        ));
+    } else if let Some((emitter, emitter_position)) = emitter {
+        // not a child of the emitter, so the sound keeps playing when the emitter is despawned
+        sound.insert((
+            TransformBundle::from_transform(Transform::from_translation(
+                emitter_position.extend(0.),
+            )),
+            FollowEmitter(emitter),
+        ));
+    }
+}
+
+type GameSound = Or<(With<IsSfx>, With<PauseWithGame>)>;
+
+// the music keeps playing under the pause menu
+fn pause_game_sounds(sinks: Query<(Option<&AudioSink>, Option<&SpatialAudioSink>), GameSound>) {
+    for (sink, spatial_sink) in &sinks {
+        if let Some(sink) = sink {
+            sink.pause();
+        }
+        if let Some(sink) = spatial_sink {
+            sink.pause();
+        }
+    }
+}
+
+fn resume_game_sounds(sinks: Query<(Option<&AudioSink>, Option<&SpatialAudioSink>), GameSound>) {
+    for (sink, spatial_sink) in &sinks {
+        if let Some(sink) = sink {
+            sink.play();
+        }
+        if let Some(sink) = spatial_sink {
+            sink.play();
+        }
+    }
+}
+
+fn update_sfx_volume(
+    audio_settings: Res<AudioSettings>,
+    sinks: Query<(&IsSfx, Option<&AudioSink>, Option<&SpatialAudioSink>)>,
+) {
+    for (sfx, sink, spatial_sink) in &sinks {
+        let volume = audio_settings.sfx_volume() * sfx.gain;
+        if let Some(sink) = sink {
+            sink.set_volume(volume);
+        }
+        if let Some(sink) = spatial_sink {
+            sink.set_volume(volume);
+        }
+    }
+}
+
+fn follow_emitters(
+    mut sounds: Query<(&FollowEmitter, &mut Transform)>,
+    emitters: Query<&GlobalTransform>,
+) {
+    for (emitter, mut transform) in &mut sounds {
+        // stays where the emitter was last seen once it is despawned
+        if let Ok(emitter_transform) = emitters.get(emitter.0) {
+            transform.translation = emitter_transform.translation().truncate().extend(0.);
+        }
+    }
 }
 
 /// Trigger this event to play a single sound effect.
-#[derive(Event)]
-pub enum PlaySfx {
+///
+/// Triggered for an entity with `trigger_targets`, or given a position with [`PlaySfx::at`],
+/// the sound comes from there and is panned and attenuated relative to the [`SpatialListener`].
+/// How many instances of the same sound play at once is limited by [`SfxLimits`].
+#[derive(Event, Copy, Clone)]
+pub struct PlaySfx {
+    pub sfx: Sfx,
+    /// Where in the world the sound comes from, for sounds of entities that are despawned.
+    pub position: Option<Vec2>,
+}
+
+impl PlaySfx {
+    pub fn new(sfx: Sfx) -> Self {
+        Self {
+            sfx,
+            position: None,
+        }
+    }
+
+    /// Plays the sound from a position in the world.
+    pub fn at(self, position: Vec2) -> Self {
+        Self {
+            position: Some(position),
+            ..self
+        }
+    }
+}
+
+/// Which sound effect a [`PlaySfx`] plays, and how.
+#[derive(Copy, Clone)]
+pub enum Sfx {
     Key(SfxKey),
-    // TODO
-    #[allow(dead_code)]
+    /// Play at the given playback speed, which also changes the pitch.
+    Pitched(SfxKey, f32),
+    /// Play with a random pitch and volume,
+    /// off by up to the given fraction in either direction.
+    Varied {
+        key: SfxKey,
+        pitch: f32,
+        volume: f32,
+    },
     RandomStep,
 }
 
@@ -40,3 +227,111 @@ fn random_step() -> SfxKey {
         .copied()
         .unwrap()
 }
+
+/// Limits how often a sound effect can be played.
+#[derive(Clone, Copy, Debug)]
+pub struct SfxLimit {
+    /// How many instances can play at the same time.
+    pub max_concurrent: usize,
+    /// How long after the last instance started a new one can be played.
+    pub cooldown: Duration,
+    /// Whether to stop the oldest instance when there are too many,
+    /// instead of not playing the new one.
+    pub replace_oldest: bool,
+}
+
+/// The [`SfxLimit`] of every sound effect, sounds without one are never limited.
+/// Sounds of the level have their own keys, so they never use up the limits of the UI sounds.
+#[derive(Resource, Clone)]
+pub struct SfxLimits(pub HashMap<SfxKey, SfxLimit>);
+
+impl Default for SfxLimits {
+    fn default() -> Self {
+        let steps = SfxLimit {
+            max_concurrent: 2,
+            cooldown: Duration::ZERO,
+            replace_oldest: true,
+        };
+        Self(
+            [
+                (
+                    SfxKey::ButtonHover,
+                    SfxLimit {
+                        max_concurrent: 2,
+                        cooldown: Duration::from_millis(50),
+                        replace_oldest: true,
+                    },
+                ),
+                (
+                    SfxKey::ButtonPress,
+                    SfxLimit {
+                        max_concurrent: 1,
+                        cooldown: Duration::from_millis(100),
+                        replace_oldest: false,
+                    },
+                ),
+                (SfxKey::Step1, steps),
+                (SfxKey::Step2, steps),
+                (SfxKey::Step3, steps),
+                (SfxKey::Step4, steps),
+                (
+                    SfxKey::Door,
+                    SfxLimit {
+                        max_concurrent: 3,
+                        cooldown: Duration::ZERO,
+                        replace_oldest: true,
+                    },
+                ),
+                (
+                    SfxKey::Dock,
+                    SfxLimit {
+                        max_concurrent: 1,
+                        cooldown: Duration::from_millis(200),
+                        replace_oldest: false,
+                    },
+                ),
+                // items next to each other are picked up at once
+                (
+                    SfxKey::Pickup,
+                    SfxLimit {
+                        max_concurrent: 4,
+                        cooldown: Duration::ZERO,
+                        replace_oldest: true,
+                    },
+                ),
+                // a fast wheel can break a few blocks at once
+                (
+                    SfxKey::Break,
+                    SfxLimit {
+                        max_concurrent: 3,
+                        cooldown: Duration::from_millis(30),
+                        replace_oldest: true,
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+/// A sound of the game other than the sound effects, like a loop,
+/// which stops while the game is paused.
+#[derive(Component)]
+pub struct PauseWithGame;
+
+/// A positional sound following the entity that emitted it.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FollowEmitter(Entity);
+
+/// Sound effect entities, so their volume can be changed and their instances counted.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct IsSfx {
+    key: SfxKey,
+    /// Volume relative to the sfx volume.
+    gain: f32,
+    /// When it started playing, in real time.
+    started: Duration,
+}
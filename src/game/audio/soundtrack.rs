@@ -1,48 +1,294 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use std::time::Duration;
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+    utils::HashSet,
+};
+
+use crate::{
+    game::assets::{HandleMap, SoundtrackKey},
+    settings::AudioSettings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
+    app.register_type::<SoundtrackFade>();
+    app.register_type::<SoundtrackLayer>();
+    app.register_type::<CrossfadeDuration>();
+    app.init_resource::<CrossfadeDuration>();
+    app.register_type::<ActiveMusicLayers>();
+    app.init_resource::<ActiveMusicLayers>();
     app.observe(play_soundtrack);
+    app.observe(play_stinger);
+    app.add_systems(Update, (fade_soundtracks, fade_layers).chain());
+}
+
+/// How much the soundtracks are turned down while a stinger plays.
+const STINGER_DUCKING: f32 = 0.3;
+
+/// The stems played in sync with a soundtrack, each faded in while its layer is active.
+/// A stem can be shorter than its soundtrack, as long as it loops a whole number of times in it.
+fn soundtrack_layers(key: SoundtrackKey) -> &'static [(MusicLayer, SoundtrackKey)] {
+    match key {
+        SoundtrackKey::Gameplay => &[
+            (MusicLayer::PowerUp, SoundtrackKey::GameplayPowerUp),
+            (MusicLayer::Tension, SoundtrackKey::GameplayTension),
+        ],
+        _ => &[],
+    }
 }
 
 fn play_soundtrack(
     trigger: Trigger<PlaySoundtrack>,
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-    soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    mut soundtrack_query: Query<(&IsSoundtrack, &mut SoundtrackFade, Option<&AudioSink>)>,
 ) {
-    for entity in &soundtrack_query {
-        commands.entity(entity).despawn_recursive();
+    let (soundtrack_key, resume) = match trigger.event() {
+        PlaySoundtrack::Key(key) => (Some(*key), false),
+        PlaySoundtrack::Resume(key) => (Some(*key), true),
+        PlaySoundtrack::Disable => (None, false),
+    };
+
+    let mut resumed = false;
+    for (soundtrack, mut fade, sink) in &mut soundtrack_query {
+        if resume && !resumed && Some(soundtrack.0) == soundtrack_key {
+            fade.target = 1.;
+            fade.despawn_when_silent = false;
+            if let Some(sink) = sink {
+                sink.play();
+            }
+            resumed = true;
+        } else {
+            fade.target = 0.;
+            // a new instance of the same track replaces this one
+            fade.despawn_when_silent |= Some(soundtrack.0) == soundtrack_key;
+        }
     }
 
-    let soundtrack_key = match trigger.event() {
-        PlaySoundtrack::Key(key) => *key,
-        PlaySoundtrack::Disable => return,
+    let Some(soundtrack_key) = soundtrack_key.filter(|_| !resumed) else {
+        return;
     };
+    commands
+        .spawn((
+            AudioSourceBundle {
+                source: soundtrack_handles[&soundtrack_key].clone_weak(),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    // faded in by `fade_soundtracks`
+                    volume: Volume::new(0.),
+                    ..default()
+                },
+            },
+            IsSoundtrack(soundtrack_key),
+            SoundtrackFade {
+                level: 0.,
+                target: 1.,
+                despawn_when_silent: false,
+            },
+        ))
+        .with_children(|children| {
+            // started together with the soundtrack, so they stay in sync
+            for (layer, stem) in soundtrack_layers(soundtrack_key) {
+                children.spawn((
+                    AudioSourceBundle {
+                        source: soundtrack_handles[stem].clone_weak(),
+                        settings: PlaybackSettings {
+                            mode: PlaybackMode::Loop,
+                            // faded in by `fade_layers`
+                            volume: Volume::new(0.),
+                            ..default()
+                        },
+                    },
+                    SoundtrackLayer {
+                        layer: *layer,
+                        level: 0.,
+                    },
+                ));
+            }
+        });
+}
+
+fn play_stinger(
+    trigger: Trigger<PlayStinger>,
+    mut commands: Commands,
+    soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    audio_settings: Res<AudioSettings>,
+) {
     commands.spawn((
         AudioSourceBundle {
-            source: soundtrack_handles[&soundtrack_key].clone_weak(),
+            source: soundtrack_handles[&trigger.event().0].clone_weak(),
             settings: PlaybackSettings {
-                mode: PlaybackMode::Loop,
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(audio_settings.music_volume()),
                 ..default()
             },
         },
-        IsSoundtrack,
+        IsStinger,
     ));
 }
 
+/// How far a fade moves this frame.
+fn fade_step(time: &Time, crossfade: &CrossfadeDuration) -> f32 {
+    if crossfade.0.is_zero() {
+        1.
+    } else {
+        time.delta_seconds() / crossfade.0.as_secs_f32()
+    }
+}
+
+fn move_towards(level: f32, target: f32, step: f32) -> f32 {
+    if level < target {
+        (level + step).min(target)
+    } else {
+        (level - step).max(target)
+    }
+}
+
+fn fade_soundtracks(
+    time: Res<Time>,
+    mut commands: Commands,
+    crossfade: Res<CrossfadeDuration>,
+    audio_settings: Res<AudioSettings>,
+    mut soundtracks: Query<(Entity, &mut SoundtrackFade, Option<&AudioSink>), With<IsSoundtrack>>,
+    stingers: Query<&AudioSink, With<IsStinger>>,
+) {
+    let step = fade_step(&time, &crossfade);
+    let ducking = if stingers.is_empty() {
+        1.
+    } else {
+        STINGER_DUCKING
+    };
+    for sink in &stingers {
+        sink.set_volume(audio_settings.music_volume());
+    }
+
+    for (entity, mut fade, sink) in &mut soundtracks {
+        fade.level = move_towards(fade.level, fade.target, step);
+
+        let Some(sink) = sink else {
+            continue;
+        };
+        sink.set_volume(audio_settings.music_volume() * fade.level * ducking);
+
+        if fade.level == 0. && fade.target == 0. {
+            if fade.despawn_when_silent {
+                commands.entity(entity).despawn_recursive();
+            } else if !sink.is_paused() {
+                // paused instead of stopped, so the track can be resumed where it was
+                sink.pause();
+            }
+        }
+    }
+}
+
+// layers follow their soundtrack's volume and pause along with it
+fn fade_layers(
+    time: Res<Time>,
+    crossfade: Res<CrossfadeDuration>,
+    audio_settings: Res<AudioSettings>,
+    active_layers: Res<ActiveMusicLayers>,
+    soundtracks: Query<(&SoundtrackFade, Option<&AudioSink>)>,
+    stingers: Query<(), With<IsStinger>>,
+    mut layers: Query<(&Parent, &mut SoundtrackLayer, Option<&AudioSink>)>,
+) {
+    let step = fade_step(&time, &crossfade);
+    let ducking = if stingers.is_empty() {
+        1.
+    } else {
+        STINGER_DUCKING
+    };
+
+    for (parent, mut layer, sink) in &mut layers {
+        let target = if active_layers.0.contains(&layer.layer) {
+            1.
+        } else {
+            0.
+        };
+        layer.level = move_towards(layer.level, target, step);
+
+        let (Ok((fade, Some(soundtrack_sink))), Some(sink)) = (soundtracks.get(parent.get()), sink)
+        else {
+            continue;
+        };
+        sink.set_volume(audio_settings.music_volume() * fade.level * layer.level * ducking);
+        if soundtrack_sink.is_paused() && !sink.is_paused() {
+            sink.pause();
+        } else if !soundtrack_sink.is_paused() && sink.is_paused() {
+            sink.play();
+        }
+    }
+}
+
 /// Trigger this event to play or disable the soundtrack.
-/// Playing a new soundtrack will overwrite the previous one.
+/// Playing a new soundtrack will fade out the previous one
+/// while fading in the new one, see [`CrossfadeDuration`].
 /// Soundtracks will loop.
 #[derive(Event)]
 pub enum PlaySoundtrack {
+    /// Play the track from the start.
     Key(SoundtrackKey),
+    /// Continue the track where it was faded out last time,
+    /// or play it from the start if it never played.
+    Resume(SoundtrackKey),
     Disable,
 }
 
-/// Marker component for the soundtrack entity so we can find it later.
+/// Trigger this event to play a one-shot piece of music over the soundtrack,
+/// which is turned down while it plays.
+#[derive(Event)]
+pub struct PlayStinger(pub SoundtrackKey);
+
+/// Optional parts of a soundtrack, see [`ActiveMusicLayers`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect)]
+pub enum MusicLayer {
+    /// While a power-up is active.
+    PowerUp,
+    /// While the player is running out of time.
+    Tension,
+}
+
+/// The layers faded in on top of the soundtrack, if it has a stem for them.
+#[derive(Resource, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct ActiveMusicLayers(pub HashSet<MusicLayer>);
+
+/// How long it takes to fade a soundtrack in or out.
+#[derive(Resource, Reflect, Clone, Copy, Debug)]
+#[reflect(Resource)]
+pub struct CrossfadeDuration(pub Duration);
+
+impl Default for CrossfadeDuration {
+    fn default() -> Self {
+        Self(Duration::from_secs_f32(1.5))
+    }
+}
+
+/// Marker component for the soundtrack entities so we can find them later.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct IsSoundtrack(SoundtrackKey);
+
+/// The volume of a soundtrack relative to the music volume,
+/// moving towards its target over the [`CrossfadeDuration`].
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct IsSoundtrack;
+struct SoundtrackFade {
+    level: f32,
+    target: f32,
+    /// Despawn once faded out, instead of pausing to resume later.
+    despawn_when_silent: bool,
+}
+
+/// A stem of the parent soundtrack and its volume relative to the soundtrack.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SoundtrackLayer {
+    layer: MusicLayer,
+    level: f32,
+}
+
+/// Marker component for the stinger entities so we can find them later.
+#[derive(Component)]
+struct IsStinger;
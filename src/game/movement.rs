@@ -10,7 +10,7 @@ use bevy_rapier2d::{dynamics::Velocity, plugin::RapierContext};
 
 use crate::{screen::Screen, AppSet};
 
-use super::spawn::player::Player;
+use super::spawn::{level::respawn::Dying, player::Player};
 
 pub(super) fn plugin(app: &mut App) {
     // Record directional input as movement controls.
@@ -39,6 +39,8 @@ pub struct MovementController {
     pub movement: Vec2,
     pub jump: Vec2,
     pub colliding: bool,
+    /// Fall through one-way platforms.
+    pub drop_through: bool,
 }
 
 #[derive(Component, Reflect, Clone)]
@@ -94,14 +96,20 @@ fn record_movement_controller(
 
         let intent = intent.normalize_or_zero();
 
+        let drop_intent = input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown);
+
         // Apply movement intent to controllers.
         controller.movement = intent;
         controller.jump = jump_intent;
+        controller.drop_through = drop_intent;
     }
 }
 
 fn apply_movement(
-    mut movement_query: Query<(&MovementController, &MovementConfig, &mut Velocity), With<Player>>,
+    mut movement_query: Query<
+        (&MovementController, &MovementConfig, &mut Velocity),
+        (With<Player>, Without<Dying>),
+    >,
 ) {
     for (controller, config, mut vel) in &mut movement_query {
         // rolling movement
@@ -1,25 +1,70 @@
+pub mod breakables;
+pub mod checkpoints;
 pub mod components;
+pub mod doors;
+pub mod enemies;
+pub mod generators;
+pub mod inventory;
 pub mod items;
+pub mod loops;
+pub mod moving_platforms;
+pub mod music;
+pub mod palette;
+pub mod platforms;
+pub mod ramps;
+pub mod respawn;
+pub mod shake;
+pub mod signals;
 pub mod spawn;
+pub mod surfaces;
 pub mod ui;
 
+use std::time::Duration;
+
 use bevy::{prelude::*, time::Stopwatch, utils::HashMap};
 use bevy_ecs_ldtk::LdtkWorldBundle;
-use items::ItemType;
+use items::{ItemType, Items};
 use ui::StartLevelUi;
 
 use crate::screen::Screen;
 
+use super::player::Player;
+
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((spawn::plugin, items::plugin, ui::plugin));
+    app.add_plugins((
+        spawn::plugin,
+        breakables::plugin,
+        checkpoints::plugin,
+        doors::plugin,
+        enemies::plugin,
+        generators::plugin,
+        items::plugin,
+        inventory::plugin,
+        loops::plugin,
+        moving_platforms::plugin,
+        ramps::plugin,
+        respawn::plugin,
+        shake::plugin,
+        signals::plugin,
+        surfaces::plugin,
+    ));
+    app.add_plugins((music::plugin, ui::plugin));
     app.observe(spawn_level);
-    app.add_systems(Update, tick_level_timer.run_if(in_state(Screen::Playing)));
+    app.add_systems(
+        Update,
+        (tick_level_timer, complete_level).run_if(in_state(Screen::Playing)),
+    );
     app.add_systems(OnExit(Screen::Playing), reset_level_timer);
 }
 
+/// Time spent in the current level, paused once the level is completed.
 #[derive(Resource, Default)]
 pub struct LevelTimer(pub Stopwatch);
 
+/// Triggered once all hampters of the current level are collected.
+#[derive(Event, Debug)]
+pub struct LevelCompleted;
+
 #[derive(Resource)]
 pub struct CurrentLevel(pub SpawnLevel);
 
@@ -46,6 +91,13 @@ impl CurrentLevel {
             .unwrap_or(&0)
     }
 
+    /// The time a good run takes, after that the music gets tense.
+    pub fn par_time(&self) -> Duration {
+        match self.0 {
+            SpawnLevel::First => Duration::from_secs(90),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn bluberries(&self) -> u8 {
         *self
@@ -98,6 +150,26 @@ fn tick_level_timer(time: Res<Time>, mut timer: ResMut<LevelTimer>) {
     timer.0.tick(time.delta());
 }
 
+fn complete_level(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut timer: ResMut<LevelTimer>,
+    player: Query<&Items, (With<Player>, Changed<Items>)>,
+) {
+    let Ok(items) = player.get_single() else {
+        return;
+    };
+    if timer.0.paused() {
+        return;
+    }
+    let hampters = items.0.get(&ItemType::Hampter).copied().unwrap_or(0);
+    if hampters >= current_level.hampters() {
+        timer.0.pause();
+        commands.trigger(LevelCompleted);
+    }
+}
+
 fn reset_level_timer(mut timer: ResMut<LevelTimer>) {
     timer.0.reset();
+    timer.0.unpause();
 }
@@ -0,0 +1,175 @@
+//! Blocks that are solid, unless the wheel crashes into them fast enough.
+//!
+//! Every block has its own collider, they aren't [`Wall`](super::components::Wall)s
+//! and so never merged with other walls, so a single block can break.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::{
+    game::{
+        assets::SfxKey,
+        audio::sfx::{PlaySfx, Sfx},
+        spawn::player::Player,
+    },
+    screen::Screen,
+    AppSet,
+};
+
+use super::{components::collision_events, shake::ScreenShake};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_int_cell::<BreakableBundle>(BREAKABLE_VALUE);
+    app.add_systems(
+        Update,
+        (break_blocks, update_debris)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+    // levels are spawned after `Update`, the blocks need their collider before the physics step
+    app.add_systems(PostUpdate, size_breakables.before(PhysicsSet::SyncBackend));
+}
+
+pub const BREAKABLE_VALUE: i32 = 29;
+
+/// Speed towards a block the wheel needs to break it.
+const BREAK_SPEED: f32 = 250.;
+
+const DEBRIS_COUNT: usize = 8;
+const DEBRIS_SECS: f32 = 0.8;
+const DEBRIS_COLOR: Color = Color::srgb(0.55, 0.45, 0.35);
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Breakable;
+
+#[derive(Clone, Default, Bundle, LdtkIntCell)]
+pub struct BreakableBundle {
+    #[with(fixed_body)]
+    pub rigid_body: RigidBody,
+    #[with(block_friction)]
+    pub friction: Friction,
+    #[with(collision_events)]
+    pub active_events: ActiveEvents,
+    pub breakable: Breakable,
+}
+
+fn fixed_body(_: IntGridCell) -> RigidBody {
+    RigidBody::Fixed
+}
+
+fn block_friction(_: IntGridCell) -> Friction {
+    Friction::new(1.0)
+}
+
+// the collider covers the whole tile, whatever the grid size of the layer
+fn size_breakables(
+    mut commands: Commands,
+    blocks: Query<(Entity, &Parent), Added<Breakable>>,
+    layers: Query<&LayerMetadata>,
+) {
+    for (block, layer) in &blocks {
+        let Ok(layer) = layers.get(layer.get()) else {
+            continue;
+        };
+        let half_size = layer.grid_size as f32 / 2.;
+        commands
+            .entity(block)
+            .insert(Collider::cuboid(half_size, half_size));
+    }
+}
+
+/// A piece of a broken block, flying away and fading out.
+#[derive(Component)]
+pub struct Debris {
+    velocity: Vec2,
+    timer: Timer,
+}
+
+fn break_blocks(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    player: Query<(Entity, &GlobalTransform, &Velocity), With<Player>>,
+    blocks: Query<&GlobalTransform, With<Breakable>>,
+    mut shake: ResMut<ScreenShake>,
+    // the velocity before the collision, the current one is already slowed down by it
+    mut previous_velocity: Local<Vec2>,
+) {
+    let Ok((player, player_transform, velocity)) = player.get_single() else {
+        return;
+    };
+
+    for collision in collisions.read() {
+        let CollisionEvent::Started(a, b, _) = collision else {
+            continue;
+        };
+        let block = if *a == player {
+            *b
+        } else if *b == player {
+            *a
+        } else {
+            continue;
+        };
+        let Ok(block_transform) = blocks.get(block) else {
+            continue;
+        };
+
+        let towards_block = (block_transform.translation() - player_transform.translation())
+            .truncate()
+            .normalize_or_zero();
+        if previous_velocity.dot(towards_block) < BREAK_SPEED {
+            continue;
+        }
+
+        commands.trigger(
+            PlaySfx::new(Sfx::Key(SfxKey::Break)).at(block_transform.translation().truncate()),
+        );
+        commands.entity(block).despawn_recursive();
+        shake.add_trauma(0.5);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..DEBRIS_COUNT {
+            let velocity = *previous_velocity * rng.gen_range(0.2..0.6)
+                + Vec2::new(rng.gen_range(-80.0..80.0), rng.gen_range(0.0..150.0));
+            commands.spawn((
+                Name::new("Debris"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: DEBRIS_COLOR,
+                        custom_size: Some(Vec2::splat(rng.gen_range(2.0..5.0))),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(block_transform.translation()),
+                    ..default()
+                },
+                Debris {
+                    velocity,
+                    timer: Timer::from_seconds(DEBRIS_SECS, TimerMode::Once),
+                },
+                StateScoped(Screen::Playing),
+            ));
+        }
+    }
+
+    *previous_velocity = velocity.linvel;
+}
+
+fn update_debris(
+    time: Res<Time>,
+    mut commands: Commands,
+    rapier_config: Res<RapierConfiguration>,
+    mut debris: Query<(Entity, &mut Debris, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut debris, mut transform, mut sprite) in &mut debris {
+        debris.timer.tick(time.delta());
+        debris.velocity += rapier_config.gravity * time.delta_seconds();
+        transform.translation += (debris.velocity * time.delta_seconds()).extend(0.);
+        transform.rotate_z(10. * time.delta_seconds());
+        sprite.color.set_alpha(debris.timer.fraction_remaining());
+
+        if debris.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Checkpoints move the [`RespawnPoint`] and record the collected items when the player touches them.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::CollisionEvent;
+
+use crate::{game::spawn::player::Player, screen::Screen, settings::AccessibilitySettings};
+
+use super::{
+    components::SensorBundle, items::Collected, palette::StatePalette, respawn::RespawnPoint,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<CheckpointBundle>("Checkpoint");
+    app.add_systems(
+        Update,
+        (
+            reach_checkpoint,
+            color_checkpoints.run_if(resource_changed::<AccessibilitySettings>),
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Checkpoint;
+
+/// Marks the checkpoint the player will respawn at.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct ActiveCheckpoint;
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct CheckpointBundle {
+    #[with(checkpoint_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[from_entity_instance]
+    pub sensor_bundle: SensorBundle,
+    pub checkpoint: Checkpoint,
+}
+
+fn checkpoint_sprite(entity_instance: &EntityInstance) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: StatePalette::NORMAL.checkpoint,
+            custom_size: Some(Vec2::new(
+                entity_instance.width as f32,
+                entity_instance.height as f32,
+            )),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn reach_checkpoint(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    mut respawn_point: ResMut<RespawnPoint>,
+    settings: Res<AccessibilitySettings>,
+    player: Query<&Transform, With<Player>>,
+    mut checkpoints: Query<(Entity, &mut Sprite, Has<ActiveCheckpoint>), With<Checkpoint>>,
+    collected: Query<Entity, With<Collected>>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(a, b, _) = collision else {
+            continue;
+        };
+
+        let (player_entity, checkpoint_entity) = if player.contains(*a) {
+            (*a, *b)
+        } else {
+            (*b, *a)
+        };
+
+        let (Ok(transform), Ok((_, _, false))) = (
+            player.get(player_entity),
+            checkpoints.get(checkpoint_entity),
+        ) else {
+            continue;
+        };
+
+        respawn_point.position = Some(transform.translation.truncate());
+        respawn_point.collected = collected.iter().collect();
+
+        // only the latest checkpoint is active
+        for (entity, mut sprite, active) in &mut checkpoints {
+            if entity == checkpoint_entity {
+                sprite.color = StatePalette::new(&settings).active_checkpoint;
+                commands.entity(entity).insert(ActiveCheckpoint);
+            } else if active {
+                sprite.color = StatePalette::new(&settings).checkpoint;
+                commands.entity(entity).remove::<ActiveCheckpoint>();
+            }
+        }
+    }
+}
+
+fn color_checkpoints(
+    settings: Res<AccessibilitySettings>,
+    mut checkpoints: Query<(&mut Sprite, Has<ActiveCheckpoint>), With<Checkpoint>>,
+) {
+    let palette = StatePalette::new(&settings);
+    for (mut sprite, active) in &mut checkpoints {
+        sprite.color = if active {
+            palette.active_checkpoint
+        } else {
+            palette.checkpoint
+        };
+    }
+}
@@ -1,10 +1,10 @@
-use std::f32::consts::PI;
-
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 
 use bevy_rapier2d::prelude::*;
 
+use super::{signals::SignalTarget, surfaces::Surface};
+
 #[derive(Clone, Default, Bundle, LdtkIntCell)]
 pub struct ColliderBundle {
     pub collider: Collider,
@@ -18,50 +18,6 @@ pub struct ColliderBundle {
     pub density: ColliderMassProperties,
 }
 
-impl From<IntGridCell> for ColliderBundle {
-    fn from(int_grid_cell: IntGridCell) -> ColliderBundle {
-        match int_grid_cell.value {
-            // 1x1 Concave bottom right
-            4 => create_ramp_collider(-7.5, 7.5, 15.0, f32::to_radians(270.), 20),
-            // 1x1 Concave bottom left
-            5 => create_ramp_collider(7.5, 7.5, 15.0, f32::to_radians(180.), 20),
-            // 1x1 Concave top left
-            6 => create_ramp_collider(-7.5, -7.5, 15.0, 0., 20),
-            // 1x1 Concave top right
-            7 => create_ramp_collider(7.5, -7.5, 15.0, f32::to_radians(90.), 20),
-            _ => ColliderBundle::default(),
-        }
-    }
-}
-
-// simple ramp collider using some magic trigonometry
-fn create_ramp_collider(
-    center_x: f32,
-    center_y: f32,
-    radius: f32,
-    start_angle: f32,
-    num_segments: usize,
-) -> ColliderBundle {
-    let vertices = (0..=num_segments).map(|i| {
-        let theta = start_angle + PI / 2. * (i as f32 / num_segments as f32);
-        let x = center_x + radius * theta.cos();
-        let y = center_y + radius * theta.sin();
-        Vec2::new(x, y)
-    });
-
-    ColliderBundle {
-        collider: Collider::polyline(vertices.collect(), None),
-        rigid_body: RigidBody::Fixed,
-        ..default()
-    }
-}
-
-#[derive(Clone, Default, Bundle, LdtkIntCell)]
-pub struct RampBundle {
-    #[from_int_grid_cell]
-    pub collider_bundle: ColliderBundle,
-}
-
 #[derive(Clone, Default, Bundle, LdtkIntCell)]
 pub struct SensorBundle {
     pub collider: Collider,
@@ -70,11 +26,17 @@ pub struct SensorBundle {
     pub rotation_constraints: LockedAxes,
 }
 
+/// Linear damping of the player, before any [`Surface`] effects.
+pub const PLAYER_LINEAR_DAMPING: f32 = 1.5;
+
+/// Radius of the player's collider, half of the 48x48 ball sprite.
+pub const PLAYER_RADIUS: f32 = 24.;
+
 impl From<&EntityInstance> for ColliderBundle {
     fn from(entity_instance: &EntityInstance) -> ColliderBundle {
         match entity_instance.identifier.as_ref() {
             "Player" => ColliderBundle {
-                collider: Collider::ball(24.), // half of the resolution (48x48 is the ball sprite)
+                collider: Collider::ball(PLAYER_RADIUS),
                 rigid_body: RigidBody::Dynamic,
                 friction: Friction::default(),
                 restitution: Restitution {
@@ -82,11 +44,38 @@ impl From<&EntityInstance> for ColliderBundle {
                     combine_rule: CoefficientCombineRule::Multiply,
                 },
                 damping: Damping {
-                    linear_damping: 1.5,
+                    linear_damping: PLAYER_LINEAR_DAMPING,
                     angular_damping: 1.0,
                 },
                 ..default()
             },
+            // resizable in LDtk, so the collider covers the whole entity
+            "Door" => ColliderBundle {
+                collider: Collider::cuboid(
+                    entity_instance.width as f32 / 2.,
+                    entity_instance.height as f32 / 2.,
+                ),
+                rigid_body: RigidBody::Fixed,
+                friction: Friction::new(1.0),
+                ..default()
+            },
+            "Patroller" => ColliderBundle {
+                collider: Collider::cuboid(
+                    entity_instance.width as f32 / 2.,
+                    entity_instance.height as f32 / 2.,
+                ),
+                rigid_body: RigidBody::Dynamic,
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                ..default()
+            },
+            // flies, so no gravity
+            "Chaser" => ColliderBundle {
+                collider: Collider::ball(entity_instance.width as f32 / 2.),
+                rigid_body: RigidBody::Dynamic,
+                rotation_constraints: LockedAxes::ROTATION_LOCKED,
+                gravity_scale: GravityScale(0.),
+                ..default()
+            },
             // Handle other entities from LDtk
             _ => ColliderBundle::default(),
         }
@@ -104,8 +93,20 @@ impl From<&EntityInstance> for SensorBundle {
                 rotation_constraints,
                 active_events: ActiveEvents::COLLISION_EVENTS,
             },
-            // TODO
-            _ => todo!(),
+            // resizable in LDtk, so the sensor covers the whole entity
+            "Hazard" | "Checkpoint" | "Generator" | "Switch" | "PressurePlate" => SensorBundle {
+                collider: Collider::cuboid(
+                    entity_instance.width as f32 / 2.,
+                    entity_instance.height as f32 / 2.,
+                ),
+                sensor: Sensor,
+                rotation_constraints,
+                active_events: ActiveEvents::COLLISION_EVENTS,
+            },
+            identifier => {
+                warn!("Entity {identifier} has no sensor, it can't be touched");
+                SensorBundle::default()
+            }
         }
     }
 }
@@ -116,4 +117,57 @@ pub struct Wall;
 #[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
 pub struct WallBundle {
     wall: Wall,
+    #[from_int_grid_cell]
+    surface: Surface,
+}
+
+/// A wall that can only be collided with from above.
+/// See [`OneWayPlatformHooks`](super::platforms::OneWayPlatformHooks).
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct OneWayPlatform;
+
+#[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
+pub struct OneWayPlatformBundle {
+    wall: Wall,
+    one_way_platform: OneWayPlatform,
+}
+
+pub const HAZARD_VALUE: i32 = 8;
+
+/// How much of its tile the sensor of a hazard tile covers,
+/// a bit less so touching its side is forgiving.
+pub const HAZARD_TILE_COVERAGE: f32 = 0.75;
+
+/// Kills the player on touch.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Hazard;
+
+/// A hazard tile, spawned without a collider,
+/// it is sized to the tile once the layer's grid size is known.
+#[derive(Clone, Default, Bundle, LdtkIntCell)]
+pub struct HazardBundle {
+    pub sensor: Sensor,
+    #[with(collision_events)]
+    pub active_events: ActiveEvents,
+    pub hazard: Hazard,
+}
+
+/// Turns on collision events for an IntGrid bundle, used with `#[with(collision_events)]`.
+pub fn collision_events(_: IntGridCell) -> ActiveEvents {
+    ActiveEvents::COLLISION_EVENTS
 }
+
+#[derive(Clone, Default, Bundle, LdtkEntity)]
+pub struct HazardEntityBundle {
+    #[from_entity_instance]
+    pub sensor_bundle: SensorBundle,
+    pub hazard: Hazard,
+    pub signal_target: SignalTarget,
+}
+
+/// How much power a machine gets from the [`Generator`](super::generators::Generator)s
+/// linked to it, from 0 to 1.
+/// Machines without this component were never linked to a generator,
+/// it drops to 0 when the generators linked to a machine are gone.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Powered(pub f32);
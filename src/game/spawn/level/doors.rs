@@ -0,0 +1,124 @@
+//! Doors that block the way until they are powered or signaled.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{
+        assets::SfxKey,
+        audio::sfx::{PlaySfx, Sfx},
+    },
+    screen::Screen,
+    settings::AccessibilitySettings,
+};
+
+use super::{
+    components::{ColliderBundle, Powered},
+    palette::StatePalette,
+    signals::{Signal, SignalTarget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<DoorBundle>("Door");
+    app.observe(signal_door);
+    app.add_systems(Update, open_doors.run_if(in_state(Screen::Playing)));
+}
+
+/// How much power a locked door needs to open.
+const DOOR_POWER_THRESHOLD: f32 = 0.9;
+
+/// A door, unlocked doors are open unless a [`Signal`] closes them.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Door {
+    pub locked: bool,
+    /// Whether the last [`Signal`] the door got was active,
+    /// `None` if the door isn't the target of any switch or pressure plate.
+    pub signaled: Option<bool>,
+}
+
+impl From<&EntityInstance> for Door {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Door {
+            locked: *entity_instance
+                .get_bool_field("locked")
+                .expect("locked field should be correctly typed"),
+            signaled: None,
+        }
+    }
+}
+
+impl Door {
+    /// Signaled doors follow their signal whether they are locked or not.
+    pub fn is_open(&self, powered: Option<&Powered>) -> bool {
+        powered.is_some_and(|powered| powered.0 >= DOOR_POWER_THRESHOLD)
+            || self.signaled.unwrap_or(!self.locked)
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct DoorBundle {
+    #[with(door_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[from_entity_instance]
+    pub door: Door,
+    pub signal_target: SignalTarget,
+}
+
+fn door_sprite(entity_instance: &EntityInstance) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: StatePalette::NORMAL.closed_door,
+            custom_size: Some(Vec2::new(
+                entity_instance.width as f32,
+                entity_instance.height as f32,
+            )),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn open_doors(
+    mut commands: Commands,
+    settings: Res<AccessibilitySettings>,
+    mut doors: Query<(
+        Entity,
+        Ref<Door>,
+        Option<&Powered>,
+        &mut Sprite,
+        Has<ColliderDisabled>,
+    )>,
+) {
+    let palette = StatePalette::new(&settings);
+    for (entity, door, powered, mut sprite, disabled) in &mut doors {
+        let open = door.is_open(powered);
+        if open != disabled {
+            // doors that start out open are opened silently
+            if !door.is_added() {
+                let speed = if open { 0.6 } else { 0.5 };
+                commands.trigger_targets(PlaySfx::new(Sfx::Pitched(SfxKey::Door, speed)), entity);
+            }
+            if open {
+                commands.entity(entity).insert(ColliderDisabled);
+            } else {
+                commands.entity(entity).remove::<ColliderDisabled>();
+            }
+        } else if !settings.is_changed() {
+            continue;
+        }
+        sprite.color = if open {
+            palette.open_door
+        } else {
+            palette.closed_door
+        };
+    }
+}
+
+fn signal_door(trigger: Trigger<Signal>, mut doors: Query<&mut Door>) {
+    if let Ok(mut door) = doors.get_mut(trigger.entity()) {
+        door.signaled = Some(trigger.event().active);
+    }
+}
@@ -0,0 +1,351 @@
+//! Enemies placed in LDtk: patrollers walking back and forth
+//! and chasers flying after the wheel when it comes close.
+//!
+//! Landing on an enemy or ramming it fast enough stuns it,
+//! stunned enemies are harmless for a while.
+//! Touching an enemy any other way kills the player.
+
+use bevy::prelude::*;
+use bevy_aseprite_ultra::prelude::{Animation, AnimationRepeat, AsepriteAnimationBundle};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{
+        animation::AsepriteAnimationBundleWrapper,
+        assets::{AsepriteKey, HandleMap, SfxKey},
+        audio::sfx::{PlaySfx, Sfx},
+        spawn::player::Player,
+    },
+    screen::Screen,
+    AppSet,
+};
+
+use super::{
+    components::ColliderBundle,
+    respawn::{Dying, PlayerDied},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<PatrollerBundle>("Patroller");
+    app.register_ldtk_entity::<ChaserBundle>("Chaser");
+    app.add_systems(
+        Update,
+        (
+            on_enemy_added,
+            (patrol, chase, tick_stunned, hit_player)
+                .chain()
+                .in_set(AppSet::Update),
+            update_enemy_sprites,
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+const PATROL_SPEED: f32 = 40.;
+const CHASE_SPEED: f32 = 70.;
+const STUN_SECS: f32 = 3.;
+
+/// Speed towards an enemy above which the wheel stuns it instead of dying.
+const RAM_SPEED: f32 = 250.;
+
+/// Upwards speed of the wheel after landing on an enemy.
+const STOMP_BOUNCE: f32 = 350.;
+
+/// Speed of the wheel after bouncing off a stunned enemy.
+const BOUNCE_OFF_SPEED: f32 = 150.;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub enum Enemy {
+    #[default]
+    Patroller,
+    Chaser,
+}
+
+impl From<&EntityInstance> for Enemy {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        match entity_instance.identifier.as_ref() {
+            "Chaser" => Enemy::Chaser,
+            _ => Enemy::Patroller,
+        }
+    }
+}
+
+impl Enemy {
+    fn aseprite_key(&self) -> AsepriteKey {
+        match self {
+            Enemy::Patroller => AsepriteKey::Beetle,
+            Enemy::Chaser => AsepriteKey::Bee,
+        }
+    }
+
+    /// Name of the animation tag to play while not stunned.
+    fn moving_tag(&self) -> &'static str {
+        match self {
+            Enemy::Patroller => "walk",
+            Enemy::Chaser => "fly",
+        }
+    }
+
+    /// Offset of the sprite, so its body lines up with the collider.
+    fn sprite_offset(&self) -> Vec3 {
+        match self {
+            Enemy::Patroller => Vec3::new(0., 5., 0.),
+            Enemy::Chaser => Vec3::new(0., 2., 0.),
+        }
+    }
+}
+
+/// Walks in one direction until a wall or a ledge is ahead.
+#[derive(Copy, Clone, PartialEq, Debug, Component)]
+pub struct Patrol {
+    /// 1 for right, -1 for left.
+    pub direction: f32,
+}
+
+impl Default for Patrol {
+    fn default() -> Self {
+        Patrol { direction: 1. }
+    }
+}
+
+/// Flies after the wheel while it is within `radius` of `home`,
+/// and back home otherwise.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Chase {
+    pub radius: f32,
+    /// Local to the enemy's parent, set once spawned.
+    pub home: Option<Vec2>,
+}
+
+impl From<&EntityInstance> for Chase {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Chase {
+            radius: *entity_instance
+                .get_float_field("radius")
+                .expect("radius field should be correctly typed"),
+            home: None,
+        }
+    }
+}
+
+/// Present on an enemy while it is stunned.
+#[derive(Component)]
+pub struct Stunned(Timer);
+
+/// The animated sprite of an enemy, a child of it.
+#[derive(Component)]
+pub struct EnemySprite;
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct PatrollerBundle {
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[from_entity_instance]
+    pub enemy: Enemy,
+    pub patrol: Patrol,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct ChaserBundle {
+    #[from_entity_instance]
+    pub collider_bundle: ColliderBundle,
+    #[from_entity_instance]
+    pub enemy: Enemy,
+    #[from_entity_instance]
+    pub chase: Chase,
+}
+
+fn on_enemy_added(
+    handles: Res<HandleMap<AsepriteKey>>,
+    mut commands: Commands,
+    enemies: Query<(Entity, &Enemy), Added<Enemy>>,
+) {
+    for (entity, enemy) in &enemies {
+        commands
+            .entity(entity)
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .with_children(|child| {
+                child.spawn((
+                    AsepriteAnimationBundleWrapper {
+                        bundle: AsepriteAnimationBundle {
+                            aseprite: handles[&enemy.aseprite_key()].clone_weak(),
+                            animation: Animation::default().with_tag(enemy.moving_tag()),
+                            transform: Transform::from_translation(enemy.sprite_offset()),
+                            ..default()
+                        },
+                    },
+                    EnemySprite,
+                ));
+            });
+    }
+}
+
+fn patrol(
+    rapier_context: Res<RapierContext>,
+    mut patrollers: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &mut Patrol,
+            &mut Velocity,
+        ),
+        Without<Stunned>,
+    >,
+) {
+    for (entity, transform, collider, mut patrol, mut velocity) in &mut patrollers {
+        let half_size = collider
+            .as_cuboid()
+            .map_or(Vec2::splat(8.), |cuboid| cuboid.half_extents());
+        let position = transform.translation().truncate();
+        let ahead = Vec2::new(patrol.direction, 0.);
+        // only level geometry counts, so enemies don't turn around because of the wheel
+        let filter = QueryFilter::exclude_dynamic()
+            .exclude_sensors()
+            .exclude_rigid_body(entity);
+
+        let wall_ahead = rapier_context
+            .cast_ray(position, ahead, half_size.x + 2., true, filter)
+            .is_some();
+        let ground_ahead = rapier_context
+            .cast_ray(
+                position + ahead * (half_size.x + 2.),
+                Vec2::NEG_Y,
+                half_size.y + 4.,
+                true,
+                filter,
+            )
+            .is_some();
+
+        // only turn at ledges while standing, otherwise falling patrollers would spin around
+        let grounded = rapier_context
+            .cast_ray(position, Vec2::NEG_Y, half_size.y + 2., true, filter)
+            .is_some();
+        if wall_ahead || (grounded && !ground_ahead) {
+            patrol.direction = -patrol.direction;
+        }
+
+        velocity.linvel.x = patrol.direction * PATROL_SPEED;
+    }
+}
+
+fn chase(
+    player: Query<&GlobalTransform, (With<Player>, Without<Dying>)>,
+    mut chasers: Query<(&GlobalTransform, &Transform, &mut Chase, &mut Velocity), Without<Stunned>>,
+) {
+    let player_position = player
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation().truncate());
+
+    for (global_transform, transform, mut chase, mut velocity) in &mut chasers {
+        let local_position = transform.translation.truncate();
+        let home = *chase.home.get_or_insert(local_position);
+        let position = global_transform.translation().truncate();
+        let home_position = position + home - local_position;
+
+        let target = player_position
+            .filter(|player| player.distance(home_position) <= chase.radius)
+            .unwrap_or(home_position);
+
+        let to_target = target - position;
+        velocity.linvel = if to_target.length() > 1. {
+            to_target.normalize() * CHASE_SPEED
+        } else {
+            Vec2::ZERO
+        };
+    }
+}
+
+fn tick_stunned(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut stunned: Query<(Entity, &Enemy, &mut Stunned, &mut Velocity)>,
+) {
+    for (entity, enemy, mut stunned, mut velocity) in &mut stunned {
+        stunned.0.tick(time.delta());
+        // patrollers still fall, chasers hover in place
+        match enemy {
+            Enemy::Patroller => velocity.linvel.x = 0.,
+            Enemy::Chaser => velocity.linvel = Vec2::ZERO,
+        }
+        if stunned.0.finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn hit_player(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    mut player: Query<(&GlobalTransform, &mut Velocity), (With<Player>, Without<Dying>)>,
+    enemies: Query<(&GlobalTransform, &Velocity, Has<Stunned>), (With<Enemy>, Without<Player>)>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(a, b, _) = collision else {
+            continue;
+        };
+        let (player_entity, enemy_entity) = if player.contains(*a) {
+            (*a, *b)
+        } else {
+            (*b, *a)
+        };
+        let (
+            Ok((player_transform, mut player_velocity)),
+            Ok((enemy_transform, enemy_velocity, stunned)),
+        ) = (player.get_mut(player_entity), enemies.get(enemy_entity))
+        else {
+            continue;
+        };
+
+        let away = (player_transform.translation() - enemy_transform.translation())
+            .truncate()
+            .normalize_or_zero();
+        // how fast the wheel moves into the enemy
+        let impact = (enemy_velocity.linvel - player_velocity.linvel).dot(away);
+        let from_above = away.y > 0.7;
+
+        if stunned {
+            player_velocity.linvel = away * BOUNCE_OFF_SPEED;
+        } else if from_above || impact >= RAM_SPEED {
+            player_velocity.linvel = if from_above {
+                Vec2::new(player_velocity.linvel.x, STOMP_BOUNCE)
+            } else {
+                away * BOUNCE_OFF_SPEED
+            };
+            commands
+                .entity(enemy_entity)
+                .insert(Stunned(Timer::from_seconds(STUN_SECS, TimerMode::Once)));
+            commands.trigger_targets(PlaySfx::new(Sfx::Pitched(SfxKey::Step3, 0.7)), enemy_entity);
+        } else {
+            commands.trigger(PlayerDied);
+            return;
+        }
+    }
+}
+
+fn update_enemy_sprites(
+    enemies: Query<(&Enemy, &Velocity, Has<Stunned>, &Children)>,
+    mut sprites: Query<(&mut Animation, &mut Sprite), With<EnemySprite>>,
+) {
+    for (enemy, velocity, stunned, children) in &enemies {
+        let tag = if stunned {
+            "stunned"
+        } else {
+            enemy.moving_tag()
+        };
+        for &child in children {
+            let Ok((mut animation, mut sprite)) = sprites.get_mut(child) else {
+                continue;
+            };
+            if animation.tag.as_deref() != Some(tag) {
+                animation.play(tag, AnimationRepeat::Loop);
+            }
+            // the sprites face right
+            if velocity.linvel.x.abs() > 1. {
+                sprite.flip_x = velocity.linvel.x < 0.;
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Generators the wheel docks into and charges by spinning in place.
+//!
+//! A generator powers the machines referenced by its `targets` field in LDtk,
+//! see [`Powered`] for how much.
+//! The wheel is let go once the generator is full or after [`DOCK_TIMEOUT`] seconds,
+//! and can only dock again after leaving it. Press down to leave a generator early.
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{
+        assets::SfxKey,
+        audio::sfx::{PlaySfx, Sfx},
+        movement::MovementController,
+        spawn::player::Player,
+    },
+    screen::Screen,
+    settings::AccessibilitySettings,
+    AppSet,
+};
+
+use super::{
+    components::{Powered, SensorBundle},
+    palette::StatePalette,
+    respawn::Dying,
+    signals::target_iids,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<GeneratorBundle>("Generator");
+    app.add_systems(
+        Update,
+        (dock_player, charge_generators, power_targets)
+            .chain()
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How strongly a docked wheel is pulled to the generator's center.
+const DOCK_STIFFNESS: f32 = 10.;
+
+/// Seconds after which a docked wheel is let go, even if the generator isn't full.
+pub const DOCK_TIMEOUT: f32 = 8.;
+
+/// Angular acceleration of a docked wheel while moving, in radians per second squared.
+const SPIN_ACCELERATION: f32 = 30.;
+
+const MAX_SPIN: f32 = 20.;
+
+/// Charge gained per radian the docked wheel turns.
+const CHARGE_PER_RADIAN: f32 = 0.01;
+
+/// Charge lost per second.
+const CHARGE_DECAY: f32 = 0.05;
+
+/// A generator powering the machines with the given entity iids.
+#[derive(Clone, PartialEq, Debug, Default, Component)]
+pub struct Generator {
+    pub targets: Vec<String>,
+}
+
+impl From<&EntityInstance> for Generator {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Generator {
+            targets: target_iids(entity_instance),
+        }
+    }
+}
+
+/// How full a [`Generator`] is, from 0 to 1.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Charge(pub f32);
+
+/// Present on a [`Generator`] while the wheel is docked in it,
+/// ticking until the wheel is let go.
+#[derive(Clone, PartialEq, Debug, Component)]
+pub struct Docked(pub Timer);
+
+impl Default for Docked {
+    fn default() -> Self {
+        Docked(Timer::from_seconds(DOCK_TIMEOUT, TimerMode::Once))
+    }
+}
+
+/// Present on a [`Generator`] that let go of the wheel, until the wheel leaves it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Released;
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct GeneratorBundle {
+    #[with(generator_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[from_entity_instance]
+    pub sensor_bundle: SensorBundle,
+    #[from_entity_instance]
+    pub generator: Generator,
+    pub charge: Charge,
+}
+
+fn generator_sprite(entity_instance: &EntityInstance) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: StatePalette::NORMAL.empty_generator,
+            custom_size: Some(Vec2::new(
+                entity_instance.width as f32,
+                entity_instance.height as f32,
+            )),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+// holds the wheel in the generator's center, where moving spins it instead,
+// until the generator is full or the wheel was held long enough
+fn dock_player(
+    time: Res<Time>,
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut player: Query<
+        (Entity, &GlobalTransform, &MovementController, &mut Velocity),
+        (With<Player>, Without<Dying>),
+    >,
+    mut generators: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Charge,
+            Option<&mut Docked>,
+            Has<Released>,
+        ),
+        With<Generator>,
+    >,
+) {
+    let Ok((player, transform, controller, mut velocity)) = player.get_single_mut() else {
+        return;
+    };
+
+    for (generator, generator_transform, charge, docked, released) in &mut generators {
+        let inside = rapier_context.intersection_pair(player, generator) == Some(true);
+        if !inside {
+            commands.entity(generator).remove::<(Docked, Released)>();
+            continue;
+        }
+        if released {
+            continue;
+        }
+        let Some(mut docked) = docked else {
+            if !controller.drop_through {
+                commands.entity(generator).insert(Docked::default());
+                commands.trigger_targets(PlaySfx::new(Sfx::Pitched(SfxKey::Dock, 0.8)), generator);
+            }
+            continue;
+        };
+        if controller.drop_through || charge.0 >= 1. || docked.0.tick(time.delta()).finished() {
+            commands
+                .entity(generator)
+                .remove::<Docked>()
+                .insert(Released);
+            continue;
+        }
+
+        let offset =
+            generator_transform.translation().truncate() - transform.translation().truncate();
+        velocity.linvel = offset * DOCK_STIFFNESS;
+        // clockwise is negative
+        velocity.angvel = (velocity.angvel
+            - controller.movement.x * SPIN_ACCELERATION * time.delta_seconds())
+        .clamp(-MAX_SPIN, MAX_SPIN);
+    }
+}
+
+fn charge_generators(
+    time: Res<Time>,
+    settings: Res<AccessibilitySettings>,
+    player: Query<&Velocity, With<Player>>,
+    mut generators: Query<(&mut Charge, &mut Sprite, Has<Docked>), With<Generator>>,
+) {
+    let spin = player
+        .get_single()
+        .map_or(0., |velocity| velocity.angvel.abs());
+
+    let palette = StatePalette::new(&settings);
+    for (mut charge, mut sprite, docked) in &mut generators {
+        let gain = if docked { spin * CHARGE_PER_RADIAN } else { 0. };
+        charge.0 = (charge.0 + (gain - CHARGE_DECAY) * time.delta_seconds()).clamp(0., 1.);
+        sprite.color = palette
+            .empty_generator
+            .mix(&palette.full_generator, charge.0);
+    }
+}
+
+// every machine gets the power of the fullest generator linked to it,
+// machines no generator is linked to anymore lose their power
+fn power_targets(
+    mut commands: Commands,
+    generators: Query<(&Generator, &Charge)>,
+    mut machines: Query<(Entity, &EntityIid, Option<&mut Powered>)>,
+) {
+    let mut power: HashMap<&str, f32> = HashMap::new();
+    for (generator, charge) in &generators {
+        for target in &generator.targets {
+            let entry = power.entry(target.as_str()).or_default();
+            *entry = entry.max(charge.0);
+        }
+    }
+
+    for (entity, iid, powered) in &mut machines {
+        let power = power.get(iid.as_str()).copied();
+        match (powered, power) {
+            (Some(mut powered), power) => powered.0 = power.unwrap_or(0.),
+            (None, Some(power)) => {
+                commands.entity(entity).insert(Powered(power));
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! An in-game inventory panel listing the player's collected items.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+
+use crate::{
+    game::assets::{HandleMap, ImageKey},
+    screen::{Pause, Screen},
+    ui::prelude::*,
+};
+
+use super::items::{BluberryTimer, ItemType, Items};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            toggle_inventory
+                .run_if(input_just_pressed(KeyCode::KeyI).and_then(in_state(Pause::Running))),
+            update_inventory_rows,
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+#[derive(Component)]
+struct InventoryPanel;
+
+/// The label of a single inventory row, showing the count and effect time of its item.
+#[derive(Component)]
+struct InventoryRow(ItemType);
+
+fn toggle_inventory(
+    mut commands: Commands,
+    panels: Query<Entity, With<InventoryPanel>>,
+    items: Query<&Items>,
+    handles: Res<HandleMap<ImageKey>>,
+) {
+    if let Ok(panel) = panels.get_single() {
+        commands.entity(panel).despawn_recursive();
+        return;
+    }
+
+    let Ok(items) = items.get_single() else {
+        return;
+    };
+
+    // keep the rows in a stable order, the HashMap doesn't
+    let mut item_types: Vec<&ItemType> = items
+        .0
+        .keys()
+        .filter(|item| !matches!(item, ItemType::None))
+        .collect();
+    item_types.sort_by_key(|item| item.to_str());
+
+    commands
+        .ui_root()
+        .insert((Name::new("Inventory"), InventoryPanel))
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|root| {
+            root.spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(10.),
+                    padding: UiRect::all(Px(20.)),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(ThemedNode::Panel)
+            .with_children(|panel| {
+                panel.header("Inventory");
+
+                if item_types.is_empty() {
+                    panel.label("Nothing collected yet");
+                }
+
+                for item in item_types {
+                    panel
+                        .spawn(NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                column_gap: Px(10.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            if let Some(key) = item.image_key() {
+                                row.icon(handles[&key].clone_weak()).insert(Style {
+                                    width: Px(32.),
+                                    height: Px(32.),
+                                    ..default()
+                                });
+                            }
+                            row.label("").insert(InventoryRow(item.clone()));
+                        });
+                }
+            });
+        });
+}
+
+fn update_inventory_rows(
+    rows: Query<(&InventoryRow, &Children)>,
+    mut texts: Query<&mut Text>,
+    items: Query<&Items>,
+    bluberry_timer: Res<BluberryTimer>,
+) {
+    let Ok(items) = items.get_single() else {
+        return;
+    };
+
+    for (row, children) in &rows {
+        let count = *items.0.get(&row.0).unwrap_or(&0);
+        let mut text = format!("{} x{}", row.0.to_str(), count);
+
+        if let ItemType::Bluberry = row.0 {
+            if count > 0 {
+                text.push_str(&format!(
+                    " - {:.2}s left",
+                    bluberry_timer.remaining(count).as_secs_f32()
+                ));
+            }
+        }
+
+        let mut iter = texts.iter_many_mut(children);
+        while let Some(mut label) = iter.fetch_next() {
+            label.sections[0].value.clone_from(&text);
+        }
+    }
+}
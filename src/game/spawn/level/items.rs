@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, time::Duration};
+
 use bevy::{asset::Handle, prelude::*, utils::HashMap};
 use bevy_ecs_ldtk::{
     app::{LdtkEntity, LdtkEntityAppExt},
@@ -5,11 +7,13 @@ use bevy_ecs_ldtk::{
     prelude::LdtkFields,
     EntityInstance,
 };
-use bevy_rapier2d::prelude::CollisionEvent;
+use bevy_rapier2d::prelude::{ColliderDisabled, CollisionEvent};
 
 use crate::{
     game::{
         animation::AsepriteAnimationBundleWrapper,
+        assets::{ImageKey, SfxKey},
+        audio::sfx::{PlaySfx, Sfx},
         movement::{MovementConfig, DEFAULT_MOVEMENT_SPEED},
     },
     screen::Screen,
@@ -25,13 +29,13 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             bob_items,
-            collect_items,
-            display_items,
+            (collect_items, hide_collected_items).chain(),
             boost_stats,
             temporary_items,
         )
             .run_if(in_state(Screen::Playing)),
     )
+    .register_type::<Items>()
     .register_type::<BluberryTimer>()
     .init_resource::<BluberryTimer>()
     .insert_resource(BobbingTimer(Timer::from_seconds(
@@ -85,6 +89,13 @@ impl Default for BluberryTimer {
     }
 }
 
+impl BluberryTimer {
+    /// Time left until all `count` bluberries have worn off.
+    pub fn remaining(&self, count: u8) -> Duration {
+        (self.0.duration() * count as u32).saturating_sub(self.0.elapsed())
+    }
+}
+
 fn temporary_items(
     time: Res<Time>,
     mut bluberry_timer: ResMut<BluberryTimer>,
@@ -112,31 +123,34 @@ fn temporary_items(
     }
 }
 
+/// Marks an item that has already been picked up.
+/// Collected items are ignored by [`collect_items`] and hidden by [`hide_collected_items`],
+/// they are only put back when the player respawns, see [`RespawnPoint`](super::respawn::RespawnPoint).
+#[derive(Component, Default)]
+pub struct Collected;
+
 fn collect_items(
     mut commands: Commands,
     mut item_holders: Query<&mut Items>,
-    items: Query<(Entity, &ItemType)>,
+    items: Query<&ItemType, Without<Collected>>,
     mut collisions: EventReader<CollisionEvent>,
 ) {
+    // item entity -> holder that gets it
+    // a BTreeMap keeps the order deterministic and dedupes events for the same item
+    let mut collected: BTreeMap<Entity, Entity> = BTreeMap::new();
+
     for collision in collisions.read() {
         match collision {
             CollisionEvent::Started(collider_a, collider_b, _) => {
-                if let (Ok(mut holder), Ok(item)) =
-                    (item_holders.get_mut(*collider_a), items.get(*collider_b))
-                {
-                    let count = holder.0.entry(item.1.clone()).or_insert(0);
-                    *count += 1;
-                    // remove entity
-                    commands.entity(item.0).despawn();
-                }
-
-                if let (Ok(mut holder), Ok(item)) =
-                    (item_holders.get_mut(*collider_b), items.get(*collider_a))
-                {
-                    let count = holder.0.entry(item.1.clone()).or_insert(0);
-                    *count += 1;
-                    // remove entity
-                    commands.entity(item.0).despawn();
+                // colliders can come in either order
+                for (holder, item) in [(*collider_a, *collider_b), (*collider_b, *collider_a)] {
+                    if item_holders.contains(holder) && items.contains(item) {
+                        // if several holders touch the same item, the lowest entity wins
+                        collected
+                            .entry(item)
+                            .and_modify(|h| *h = (*h).min(holder))
+                            .or_insert(holder);
+                    }
                 }
             }
             CollisionEvent::Stopped(_, _, _) => {
@@ -144,20 +158,27 @@ fn collect_items(
             }
         }
     }
-}
 
-fn display_items(items: Query<&mut Items>, inp: Res<ButtonInput<KeyCode>>) {
-    if !inp.just_pressed(KeyCode::KeyI) {
-        return;
-    }
-    for i in items.iter() {
-        for (k, v) in i.0.iter() {
-            println!("Item:{:?}", (k, v));
+    for (item, holder) in collected {
+        if let (Ok(mut holder), Ok(item_type)) = (item_holders.get_mut(holder), items.get(item)) {
+            let count = holder.0.entry(item_type.clone()).or_insert(0);
+            *count += 1;
         }
+        commands.entity(item).insert(Collected);
+        commands.trigger_targets(PlaySfx::new(Sfx::Pitched(SfxKey::Pickup, 1.5)), item);
+    }
+}
+
+fn hide_collected_items(mut commands: Commands, items: Query<Entity, Added<Collected>>) {
+    for item in &items {
+        commands
+            .entity(item)
+            .insert((Visibility::Hidden, ColliderDisabled));
     }
 }
 
-#[derive(Clone, Component, Debug, Eq, Default, PartialEq)]
+#[derive(Clone, Component, Debug, Eq, Default, PartialEq, Reflect)]
+#[reflect(Component)]
 pub struct Items(pub HashMap<ItemType, u8>);
 
 impl From<&EntityInstance> for Items {
@@ -166,8 +187,7 @@ impl From<&EntityInstance> for Items {
             entity_instance
                 .iter_enums_field("items")
                 .expect("items field should be correctly typed")
-                .cloned()
-                .map(|item| (ItemType::from_str(&item).unwrap_or_default(), 0))
+                .map(|item| (ItemType::from_str(item).unwrap_or_default(), 0))
                 .collect(),
         )
     }
@@ -178,7 +198,7 @@ use std::str::FromStr;
 
 macro_rules! generate_item_type_and_bundles {
     ($($item:ident),*) => {
-        #[derive(Default, Debug, Component, Clone, Eq, PartialEq, Hash)]
+        #[derive(Default, Debug, Component, Clone, Eq, PartialEq, Hash, Reflect)]
         pub enum ItemType {
             #[default]
             None, // used
@@ -239,3 +259,14 @@ macro_rules! generate_item_type_and_bundles {
 }
 
 generate_item_type_and_bundles!(Hampter, Bluberry);
+
+impl ItemType {
+    /// The image used to show this item in the UI.
+    pub fn image_key(&self) -> Option<ImageKey> {
+        match self {
+            ItemType::None => None,
+            ItemType::Hampter => Some(ImageKey::Hampter),
+            ItemType::Bluberry => Some(ImageKey::Bluberry),
+        }
+    }
+}
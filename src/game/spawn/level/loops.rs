@@ -0,0 +1,118 @@
+//! Loop-the-loops, where the wheel sticks to a circular track when it is fast enough.
+//!
+//! The track itself is painted with ramp tiles, the `Loop` entity marks the
+//! inside of the ring. While the wheel rolls along the track fast enough,
+//! gravity pulls it towards the track instead of down.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{game::spawn::player::Player, screen::Screen, AppSet};
+
+use super::{components::PLAYER_RADIUS, respawn::Dying};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<LoopBundle>("Loop");
+    app.add_systems(
+        Update,
+        stick_to_loops
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// How far the wheel may be away from the track and still stick to it.
+const TRACK_MARGIN: f32 = 4.;
+
+/// The wheel never sticks below this speed, even near the bottom of the loop.
+const MIN_LOOP_SPEED: f32 = 100.;
+
+/// A circular track, its size is the inside of the ring.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Loop {
+    pub radius: f32,
+}
+
+impl From<&EntityInstance> for Loop {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        Loop {
+            radius: entity_instance.width.min(entity_instance.height) as f32 / 2.,
+        }
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct LoopBundle {
+    #[from_entity_instance]
+    pub track: Loop,
+}
+
+/// Present on the player while it sticks to a [`Loop`].
+#[derive(Copy, Clone, PartialEq, Debug, Component)]
+pub struct InLoop {
+    /// The player's [`GravityScale`] before entering the loop, restored when leaving it.
+    pub previous_gravity_scale: f32,
+}
+
+fn stick_to_loops(
+    time: Res<Time>,
+    mut commands: Commands,
+    rapier_config: Res<RapierConfiguration>,
+    loops: Query<(&Loop, &GlobalTransform)>,
+    mut player: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Velocity,
+            &mut GravityScale,
+            Option<&InLoop>,
+            Has<Dying>,
+        ),
+        With<Player>,
+    >,
+) {
+    let Ok((entity, transform, mut velocity, mut gravity_scale, in_loop, dying)) =
+        player.get_single_mut()
+    else {
+        return;
+    };
+
+    let gravity = rapier_config.gravity.length();
+    let position = transform.translation().truncate();
+
+    // direction from the loop's center to the track below the wheel
+    let outward = loops.iter().find_map(|(track, loop_transform)| {
+        let offset = position - loop_transform.translation().truncate();
+        let distance = offset.length();
+        let on_track =
+            distance <= track.radius && distance >= track.radius - PLAYER_RADIUS - TRACK_MARGIN;
+        let outward = offset.normalize_or_zero();
+        // speed along the track
+        let speed = velocity.linvel.perp_dot(outward).abs();
+        // the track has to push the wheel into the circle harder than gravity pulls it away
+        let radius = track.radius - PLAYER_RADIUS;
+        let fast_enough = speed >= MIN_LOOP_SPEED && speed * speed / radius >= gravity * outward.y;
+        (on_track && fast_enough).then_some(outward)
+    });
+
+    match outward.filter(|_| !dying) {
+        Some(outward) => {
+            // gravity pulls towards the track, its normal gravity is turned off
+            velocity.linvel += outward * gravity * time.delta_seconds();
+            if in_loop.is_none() {
+                commands.entity(entity).insert(InLoop {
+                    previous_gravity_scale: gravity_scale.0,
+                });
+                gravity_scale.0 = 0.;
+            }
+        }
+        // too slow or left the loop, so normal gravity takes over again
+        None => {
+            if let Some(in_loop) = in_loop {
+                gravity_scale.0 = in_loop.previous_gravity_scale;
+                commands.entity(entity).remove::<InLoop>();
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Platforms moving along a path of points set in LDtk.
+//!
+//! They are kinematic bodies, so rapier knows their velocity
+//! and the friction carries the wheel along.
+//! Platforms linked to a generator move as fast as it is charged,
+//! which makes lifts that only go while the wheel keeps spinning.
+//! Platforms targeted by a switch or pressure plate only move while it is active.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{screen::Screen, AppSet};
+
+use super::{
+    components::Powered,
+    signals::{Signal, SignalTarget},
+    surfaces::Surface,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<MovingPlatformBundle>("MovingPlatform");
+    app.observe(signal_moving_platform);
+    app.add_systems(
+        Update,
+        (
+            start_moving_platforms,
+            move_platforms.in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+const MOVING_PLATFORM_COLOR: Color = Color::srgb(0.45, 0.35, 0.3);
+
+/// A platform following a path, set by the entity's fields in LDtk.
+#[derive(Clone, PartialEq, Debug, Default, Component)]
+pub struct MovingPlatform {
+    /// The points after the start, in tiles relative to the start.
+    pub path: Vec<IVec2>,
+    /// Pixels per second.
+    pub speed: f32,
+    /// Seconds to wait at every point.
+    pub wait: f32,
+    /// Goes back along the path at its end, instead of straight back to the start.
+    pub ping_pong: bool,
+    /// Set by [`Signal`]s.
+    pub running: bool,
+}
+
+impl From<&EntityInstance> for MovingPlatform {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        // LDtk's y axis points down
+        let path = entity_instance
+            .iter_points_field("path")
+            .expect("path field should be correctly typed")
+            .map(|point| (*point - entity_instance.grid) * IVec2::new(1, -1))
+            .collect();
+
+        MovingPlatform {
+            path,
+            speed: *entity_instance
+                .get_float_field("speed")
+                .expect("speed field should be correctly typed"),
+            wait: *entity_instance
+                .get_float_field("wait")
+                .expect("wait field should be correctly typed"),
+            ping_pong: *entity_instance
+                .get_bool_field("ping_pong")
+                .expect("ping_pong field should be correctly typed"),
+            running: true,
+        }
+    }
+}
+
+/// Where a [`MovingPlatform`] is on its way.
+#[derive(Clone, PartialEq, Debug, Component)]
+pub struct PlatformRoute {
+    /// The path in pixels, local to the platform's parent, starting at the platform's start.
+    points: Vec<Vec2>,
+    target: usize,
+    backwards: bool,
+    wait: Timer,
+}
+
+impl PlatformRoute {
+    fn advance(&mut self, ping_pong: bool) {
+        let last = self.points.len() - 1;
+        self.target = match (ping_pong, self.backwards, self.target) {
+            (true, false, target) if target == last => {
+                self.backwards = true;
+                target - 1
+            }
+            (true, true, 0) => {
+                self.backwards = false;
+                1
+            }
+            (true, true, target) => target - 1,
+            (_, _, target) => (target + 1) % self.points.len(),
+        };
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct MovingPlatformBundle {
+    #[with(moving_platform_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[with(moving_platform_collider)]
+    pub collider: Collider,
+    #[from_entity_instance]
+    pub moving_platform: MovingPlatform,
+    pub signal_target: SignalTarget,
+}
+
+fn moving_platform_sprite(entity_instance: &EntityInstance) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: MOVING_PLATFORM_COLOR,
+            custom_size: Some(Vec2::new(
+                entity_instance.width as f32,
+                entity_instance.height as f32,
+            )),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn moving_platform_collider(entity_instance: &EntityInstance) -> Collider {
+    Collider::cuboid(
+        entity_instance.width as f32 / 2.,
+        entity_instance.height as f32 / 2.,
+    )
+}
+
+// the grid size is only known from the layer, so the route is built once the platform is spawned
+fn start_moving_platforms(
+    mut commands: Commands,
+    platforms: Query<(Entity, &MovingPlatform, &Transform, &Parent), Added<MovingPlatform>>,
+    layers: Query<&LayerMetadata>,
+) {
+    for (entity, platform, transform, parent) in &platforms {
+        let Ok(layer) = layers.get(parent.get()) else {
+            continue;
+        };
+        let start = transform.translation.truncate();
+        let points: Vec<Vec2> = std::iter::once(start)
+            .chain(
+                platform
+                    .path
+                    .iter()
+                    .map(|offset| start + offset.as_vec2() * layer.grid_size as f32),
+            )
+            .collect();
+
+        let mut entity = commands.entity(entity);
+        entity.insert((
+            RigidBody::KinematicPositionBased,
+            Surface::Stone,
+            Surface::Stone.friction(),
+        ));
+        if points.len() > 1 {
+            entity.insert(PlatformRoute {
+                points,
+                target: 1,
+                backwards: false,
+                wait: Timer::from_seconds(0., TimerMode::Once),
+            });
+        }
+    }
+}
+
+fn move_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(
+        &MovingPlatform,
+        &mut PlatformRoute,
+        &mut Transform,
+        Option<&Powered>,
+    )>,
+) {
+    for (platform, mut route, mut transform, powered) in &mut platforms {
+        if !platform.running {
+            continue;
+        }
+        route.wait.tick(time.delta());
+        if !route.wait.finished() {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let target = route.points[route.target];
+        let power = powered.map_or(1., |powered| powered.0);
+        let step = platform.speed * power * time.delta_seconds();
+        if step <= 0. {
+            continue;
+        }
+
+        if position.distance(target) <= step {
+            transform.translation = target.extend(transform.translation.z);
+            route.advance(platform.ping_pong);
+            route.wait = Timer::from_seconds(platform.wait, TimerMode::Once);
+        } else {
+            let next = position + (target - position).normalize() * step;
+            transform.translation = next.extend(transform.translation.z);
+        }
+    }
+}
+
+fn signal_moving_platform(trigger: Trigger<Signal>, mut platforms: Query<&mut MovingPlatform>) {
+    if let Ok(mut platform) = platforms.get_mut(trigger.entity()) {
+        platform.running = trigger.event().active;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(point_count: usize) -> PlatformRoute {
+        PlatformRoute {
+            points: (0..point_count).map(|x| Vec2::new(x as f32, 0.)).collect(),
+            target: 1,
+            backwards: false,
+            wait: Timer::from_seconds(0., TimerMode::Once),
+        }
+    }
+
+    fn targets(route: &mut PlatformRoute, ping_pong: bool, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                route.advance(ping_pong);
+                route.target
+            })
+            .collect()
+    }
+
+    #[test]
+    fn looping_route_goes_back_to_the_start() {
+        assert_eq!(targets(&mut route(3), false, 5), vec![2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn ping_pong_route_turns_at_both_ends() {
+        let mut route = route(3);
+        assert_eq!(targets(&mut route, true, 6), vec![2, 1, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn ping_pong_route_of_two_points_goes_back_and_forth() {
+        assert_eq!(targets(&mut route(2), true, 4), vec![0, 1, 0, 1]);
+    }
+}
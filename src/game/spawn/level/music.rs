@@ -0,0 +1,61 @@
+//! Fades the layers of the gameplay soundtrack in and out with the state of the level,
+//! and plays a stinger when it is completed.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        assets::SoundtrackKey,
+        audio::soundtrack::{ActiveMusicLayers, MusicLayer, PlayStinger},
+        spawn::player::Player,
+    },
+    screen::Screen,
+};
+
+use super::{
+    items::{ItemType, Items},
+    CurrentLevel, LevelCompleted, LevelTimer,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(play_level_complete_stinger);
+    app.add_systems(
+        Update,
+        update_music_layers.run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), clear_music_layers);
+}
+
+fn update_music_layers(
+    current_level: Res<CurrentLevel>,
+    level_timer: Res<LevelTimer>,
+    player: Query<&Items, With<Player>>,
+    mut active_layers: ResMut<ActiveMusicLayers>,
+) {
+    let Ok(items) = player.get_single() else {
+        return;
+    };
+    let mut layers = ActiveMusicLayers::default();
+    // there is nothing left to be tense about once the level is completed
+    if !level_timer.0.paused() {
+        if items
+            .0
+            .get(&ItemType::Bluberry)
+            .is_some_and(|count| *count > 0)
+        {
+            layers.0.insert(MusicLayer::PowerUp);
+        }
+        if level_timer.0.elapsed() > current_level.par_time() {
+            layers.0.insert(MusicLayer::Tension);
+        }
+    }
+    active_layers.set_if_neq(layers);
+}
+
+fn play_level_complete_stinger(_trigger: Trigger<LevelCompleted>, mut commands: Commands) {
+    commands.trigger(PlayStinger(SoundtrackKey::LevelComplete));
+}
+
+fn clear_music_layers(mut active_layers: ResMut<ActiveMusicLayers>) {
+    active_layers.0.clear();
+}
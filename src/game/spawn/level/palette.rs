@@ -0,0 +1,57 @@
+//! The colors showing the state of level objects and the HUD,
+//! switched by [`AccessibilitySettings::colorblind`].
+//!
+//! The colorblind palette uses orange and blue instead of red and green,
+//! and differs in brightness where there is no second color.
+
+use bevy::prelude::*;
+
+use crate::settings::AccessibilitySettings;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StatePalette {
+    pub inactive_signal: Color,
+    pub active_signal: Color,
+    pub checkpoint: Color,
+    pub active_checkpoint: Color,
+    pub empty_generator: Color,
+    pub full_generator: Color,
+    pub closed_door: Color,
+    pub open_door: Color,
+    /// Tint of the HUD icons of the hampters that aren't collected yet.
+    pub missing_hampter: Color,
+}
+
+impl StatePalette {
+    pub const NORMAL: StatePalette = StatePalette {
+        inactive_signal: Color::srgba(0.6, 0.3, 0.3, 0.8),
+        active_signal: Color::srgba(0.388, 0.78, 0.302, 0.8),
+        checkpoint: Color::srgba(0.6, 0.6, 0.6, 0.6),
+        active_checkpoint: Color::srgba(0.388, 0.78, 0.302, 0.8),
+        empty_generator: Color::srgba(0.5, 0.5, 0.5, 0.6),
+        full_generator: Color::srgba(1., 0.85, 0.2, 0.9),
+        closed_door: Color::srgb(0.55, 0.4, 0.25),
+        open_door: Color::srgba(0.55, 0.4, 0.25, 0.2),
+        missing_hampter: Color::WHITE,
+    };
+
+    pub const COLORBLIND: StatePalette = StatePalette {
+        inactive_signal: Color::srgba(0.902, 0.624, 0., 0.8),
+        active_signal: Color::srgba(0., 0.447, 0.698, 0.8),
+        checkpoint: Color::srgba(0.6, 0.6, 0.6, 0.6),
+        active_checkpoint: Color::srgba(0., 0.447, 0.698, 0.8),
+        empty_generator: Color::srgba(0.5, 0.5, 0.5, 0.6),
+        full_generator: Color::srgba(0.337, 0.706, 0.914, 0.9),
+        closed_door: Color::srgb(0.55, 0.4, 0.25),
+        open_door: Color::srgba(0.337, 0.706, 0.914, 0.25),
+        missing_hampter: Color::srgb(0.35, 0.35, 0.35),
+    };
+
+    pub fn new(settings: &AccessibilitySettings) -> &'static StatePalette {
+        if settings.colorblind {
+            &Self::COLORBLIND
+        } else {
+            &Self::NORMAL
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! One-way platforms, which the wheel can jump through from below and land on from above.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use crate::game::movement::MovementController;
+
+use super::components::OneWayPlatform;
+
+/// How far (in radians) from straight up a contact normal may point
+/// for the contact to count as landing on top of a platform.
+const ALLOWED_ANGLE: f32 = 0.8;
+
+// same states as rapier's `update_as_oneway_platform`, stored in the manifold's user data
+const CONTACT_CONFIGURATION_UNKNOWN: u32 = 0;
+const CONTACT_CURRENTLY_ALLOWED: u32 = 1;
+const CONTACT_CURRENTLY_FORBIDDEN: u32 = 2;
+
+/// Physics hooks filtering out contacts with [`OneWayPlatform`]s,
+/// unless they come from above.
+///
+/// This does what `update_as_oneway_platform` does, but with the world-space
+/// normal, as the wheel's local normal rotates with it.
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    platforms: Query<'w, 's, (), With<OneWayPlatform>>,
+    controllers: Query<'w, 's, &'static MovementController>,
+}
+
+impl BevyPhysicsHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_solver_contacts(&self, context: ContactModificationContextView) {
+        // normal pointing from the platform towards the other collider
+        let (normal, other) = if self.platforms.contains(context.collider1()) {
+            (*context.raw.normal, context.collider2())
+        } else if self.platforms.contains(context.collider2()) {
+            (-*context.raw.normal, context.collider1())
+        } else {
+            return;
+        };
+
+        if self
+            .controllers
+            .get(other)
+            .is_ok_and(|controller| controller.drop_through)
+        {
+            context.raw.solver_contacts.clear();
+            *context.raw.user_data = CONTACT_CURRENTLY_FORBIDDEN;
+            return;
+        }
+
+        let contact_is_ok = normal.y >= ALLOWED_ANGLE.cos();
+
+        match *context.raw.user_data {
+            CONTACT_CONFIGURATION_UNKNOWN => {
+                if contact_is_ok {
+                    *context.raw.user_data = CONTACT_CURRENTLY_ALLOWED;
+                } else {
+                    context.raw.solver_contacts.clear();
+                    // the normal can be zero when barely touching, so wait for the next frame then
+                    if normal.norm_squared() > 0.1 {
+                        *context.raw.user_data = CONTACT_CURRENTLY_FORBIDDEN;
+                    }
+                }
+            }
+            CONTACT_CURRENTLY_FORBIDDEN => {
+                // keep passing through until nothing penetrates anymore
+                if contact_is_ok && context.raw.solver_contacts.iter().all(|c| c.dist > 0.0) {
+                    *context.raw.user_data = CONTACT_CURRENTLY_ALLOWED;
+                } else {
+                    context.raw.solver_contacts.clear();
+                }
+            }
+            _ => {
+                if context.raw.solver_contacts.is_empty() {
+                    *context.raw.user_data = CONTACT_CONFIGURATION_UNKNOWN;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,629 @@
+//! Ramps, merged with each other and the walls right next to them into collider chains.
+//!
+//! There are curves with a radius of 1 to 3 tiles and 45° and 22.5° slopes,
+//! each filling one of the four corners of their tiles.
+//!
+//! Spawning a polyline for every ramp tile leaves tiny seams between the
+//! ramps and the walls they lead onto, which the wheel catches on.
+//! Instead, the ramp tiles are flagged like the walls and their surfaces
+//! are chained together per layer afterwards.
+//! The walls chained with the ramps are left out of the merged wall rectangles,
+//! see [`is_chained_wall`].
+
+use std::f32::consts::PI;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    components::{OneWayPlatform, Wall},
+    surfaces::Surface,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    for (first_value, _) in RAMP_VALUES {
+        for value in first_value..first_value + 4 {
+            app.register_ldtk_int_cell::<RampBundle>(value);
+        }
+    }
+    app.add_systems(Update, spawn_ramp_collision);
+}
+
+/// The surface of all ramps. The walls chained with them keep their own surface,
+/// with a collider per surface.
+pub const RAMP_SURFACE: Surface = Surface::Dirt;
+
+/// Number of line segments per quarter circle with a radius of one tile.
+const ARC_SEGMENTS: usize = 20;
+
+/// The IntGrid value of the first of the four corners of every ramp shape.
+/// The corners follow in the order of [`Corner`]'s variants.
+const RAMP_VALUES: [(i32, RampShape); 5] = [
+    (4, RampShape::Curve { size: 1 }),
+    (13, RampShape::Curve { size: 2 }),
+    (17, RampShape::Curve { size: 3 }),
+    (
+        21,
+        RampShape::Slope {
+            degrees: 45.,
+            height: 1,
+        },
+    ),
+    (
+        25,
+        RampShape::Slope {
+            degrees: 22.5,
+            height: 2,
+        },
+    ),
+];
+
+/// The corner of a ramp that is filled.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Corner {
+    #[default]
+    BottomRight,
+    BottomLeft,
+    TopRight,
+    TopLeft,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RampShape {
+    /// A concave quarter circle, `size` tiles wide and tall.
+    Curve { size: i32 },
+    /// A straight slope rising `height` tiles at an angle of `degrees`.
+    /// It is as many whole tiles wide as it needs,
+    /// the rest of its top row is flat.
+    Slope { degrees: f32, height: i32 },
+}
+
+impl Default for RampShape {
+    fn default() -> Self {
+        RampShape::Curve { size: 1 }
+    }
+}
+
+/// A ramp tile. Shapes bigger than one tile are painted as a block
+/// of tiles with the same IntGrid value.
+#[derive(Copy, Clone, PartialEq, Debug, Default, Component)]
+pub struct Ramp {
+    pub shape: RampShape,
+    pub corner: Corner,
+}
+
+impl From<IntGridCell> for Ramp {
+    fn from(int_grid_cell: IntGridCell) -> Self {
+        RAMP_VALUES
+            .into_iter()
+            .find(|(first_value, _)| (*first_value..first_value + 4).contains(&int_grid_cell.value))
+            .map(|(first_value, shape)| Ramp {
+                shape,
+                corner: match int_grid_cell.value - first_value {
+                    0 => Corner::BottomRight,
+                    1 => Corner::BottomLeft,
+                    2 => Corner::TopRight,
+                    _ => Corner::TopLeft,
+                },
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Ramp {
+    /// Width and height of the whole ramp in tiles.
+    fn size(&self) -> IVec2 {
+        match self.shape {
+            RampShape::Curve { size } => IVec2::splat(size),
+            RampShape::Slope { degrees, height } => {
+                // rounding errors must not add a tile to a 45° slope
+                let width = (slope_run(degrees, height) - 1e-3).ceil() as i32;
+                IVec2::new(width, height)
+            }
+        }
+    }
+
+    /// The surface of the ramp, in tiles relative to the bottom left corner of the whole ramp.
+    fn surface(&self) -> Vec<Vec2> {
+        match self.shape {
+            RampShape::Curve { size } => {
+                let r = size as f32;
+                let segments = ARC_SEGMENTS * size as usize;
+                match self.corner {
+                    Corner::BottomRight => arc(Vec2::new(0., r), r, 270_f32.to_radians(), segments),
+                    Corner::BottomLeft => arc(Vec2::new(r, r), r, 180_f32.to_radians(), segments),
+                    Corner::TopRight => arc(Vec2::new(0., 0.), r, 0., segments),
+                    Corner::TopLeft => arc(Vec2::new(r, 0.), r, 90_f32.to_radians(), segments),
+                }
+            }
+            RampShape::Slope { degrees, height } => {
+                let size = self.size().as_vec2();
+                let h = height as f32;
+                let run = slope_run(degrees, height);
+                // rising from the bottom left, then flat until the right edge
+                let mut surface = vec![Vec2::ZERO, Vec2::new(run, h)];
+                if size.x - run > 1e-3 {
+                    surface.push(Vec2::new(size.x, h));
+                }
+                // the other corners are mirrored or turned around
+                let transform = |point: Vec2| match self.corner {
+                    Corner::BottomRight => point,
+                    Corner::BottomLeft => Vec2::new(size.x - point.x, point.y),
+                    Corner::TopRight => Vec2::new(point.x, size.y - point.y),
+                    Corner::TopLeft => size - point,
+                };
+                surface.into_iter().map(transform).collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default, Bundle, LdtkIntCell)]
+pub struct RampBundle {
+    #[from_int_grid_cell]
+    pub ramp: Ramp,
+}
+
+/// The chained colliders of the ramps of a layer and the walls of one surface next to them,
+/// a child of the layer's level.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Component)]
+pub struct RampCollider {
+    pub layer: Entity,
+}
+
+/// Whether a wall tile is chained with the ramps around it,
+/// instead of being merged with the other walls.
+pub fn is_chained_wall(
+    coords: GridCoords,
+    one_way: bool,
+    is_ramp: impl Fn(GridCoords) -> bool,
+) -> bool {
+    !one_way && (-1..=1).any(|x| (-1..=1).any(|y| is_ramp(coords + GridCoords::new(x, y))))
+}
+
+/// How many tiles wide the sloped part of a slope is.
+fn slope_run(degrees: f32, height: i32) -> f32 {
+    height as f32 / degrees.to_radians().tan()
+}
+
+// quarter circle using some magic trigonometry
+fn arc(center: Vec2, radius: f32, start_angle: f32, segments: usize) -> Vec<Vec2> {
+    (0..=segments)
+        .map(|i| {
+            let theta = start_angle + PI / 2. * (i as f32 / segments as f32);
+            center + radius * Vec2::new(theta.cos(), theta.sin())
+        })
+        .collect()
+}
+
+/// Splits the ramp tiles into whole ramps, returning the bottom left tile of each.
+///
+/// Tiles are taken bottom to top, left to right, so every tile not yet
+/// part of a ramp is the bottom left corner of a new one.
+fn find_ramp_origins(ramps: &HashMap<GridCoords, Ramp>) -> Vec<(GridCoords, Ramp)> {
+    let mut tiles: Vec<(&GridCoords, &Ramp)> = ramps.iter().collect();
+    tiles.sort_by_key(|(coords, _)| (coords.y, coords.x));
+
+    let mut taken: HashSet<GridCoords> = HashSet::new();
+    let mut origins = Vec::new();
+
+    for (&origin, &ramp) in tiles {
+        if taken.contains(&origin) {
+            continue;
+        }
+        let size = ramp.size();
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let coords = origin + GridCoords::new(x, y);
+                if ramps.get(&coords) == Some(&ramp) {
+                    taken.insert(coords);
+                } else {
+                    warn!("Ramp at {origin:?} is missing its tile at {coords:?}");
+                }
+            }
+        }
+        origins.push((origin, ramp));
+    }
+
+    origins
+}
+
+/// Spawns one compound collider per layer and surface containing all of its ramps.
+///
+/// The algorithm:
+/// 1. groups the ramp tiles into whole ramps and takes their surfaces
+/// 2. adds the exposed faces of the chained walls next to a ramp,
+///    so the wheel rolls from a wall onto a ramp without hitting a seam
+/// 3. joins all pieces of the same surface sharing an end point into chains
+/// 4. removes the vertices in the middle of straight lines
+/// 5. spawns a polyline for every chain
+///
+/// Whenever ramps are added to a layer, its previous colliders are replaced.
+pub(super) fn spawn_ramp_collision(
+    mut commands: Commands,
+    ramp_query: Query<&Parent, Added<Ramp>>,
+    all_ramps: Query<(&GridCoords, &Ramp, &Parent)>,
+    wall_query: Query<
+        (&GridCoords, &Parent, Option<&Surface>),
+        (With<Wall>, Without<OneWayPlatform>),
+    >,
+    layer_query: Query<(&LayerMetadata, &Parent)>,
+    ramp_colliders: Query<(Entity, &RampCollider)>,
+) {
+    // an intgrid tile's direct parent is its layer entity
+    let layers: HashSet<Entity> = ramp_query.iter().map(|parent| parent.get()).collect();
+
+    for layer in layers {
+        let Ok((layer_metadata, level)) = layer_query.get(layer) else {
+            continue;
+        };
+        let grid_size = layer_metadata.grid_size as f32;
+
+        let ramps: HashMap<GridCoords, Ramp> = all_ramps
+            .iter()
+            .filter(|(_, _, parent)| parent.get() == layer)
+            .map(|(&coords, &ramp, _)| (coords, ramp))
+            .collect();
+        let walls: HashMap<GridCoords, Surface> = wall_query
+            .iter()
+            .filter(|(_, parent, _)| parent.get() == layer)
+            .map(|(&coords, _, surface)| (coords, surface.copied().unwrap_or_default()))
+            .collect();
+
+        let pieces = surface_pieces(&ramps, &walls);
+
+        for (entity, collider) in &ramp_colliders {
+            if collider.layer == layer {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        for (surface, pieces) in pieces {
+            let shapes: Vec<(Vect, Rot, Collider)> = chain_pieces(pieces)
+                .into_iter()
+                .map(|chain| {
+                    let chain = chain.into_iter().map(|point| point * grid_size).collect();
+                    (Vec2::ZERO, 0., Collider::polyline(chain, None))
+                })
+                .collect();
+
+            // a child of the level, so it is positioned relative to it
+            // and despawned with it
+            commands.entity(level.get()).with_children(|level| {
+                level.spawn((
+                    Name::new("Ramp colliders"),
+                    RampCollider { layer },
+                    Collider::compound(shapes),
+                    RigidBody::Fixed,
+                    surface,
+                    surface.friction(),
+                    surface.restitution(),
+                    TransformBundle::default(),
+                ));
+            });
+        }
+    }
+}
+
+/// The ramp surfaces and the exposed faces of the walls chained with them,
+/// in tiles and by surface.
+fn surface_pieces(
+    ramps: &HashMap<GridCoords, Ramp>,
+    walls: &HashMap<GridCoords, Surface>,
+) -> HashMap<Surface, Vec<Vec<Vec2>>> {
+    let is_solid = |coords: &GridCoords| walls.contains_key(coords) || ramps.contains_key(coords);
+
+    let mut pieces: HashMap<Surface, Vec<Vec<Vec2>>> = HashMap::new();
+
+    for (coords, ramp) in find_ramp_origins(ramps) {
+        let corner = Vec2::new(coords.x as f32, coords.y as f32);
+        pieces.entry(RAMP_SURFACE).or_default().push(
+            ramp.surface()
+                .into_iter()
+                .map(|point| corner + point)
+                .collect(),
+        );
+    }
+
+    let chained_walls = walls.iter().filter(|(&coords, _)| {
+        is_chained_wall(coords, false, |coords| ramps.contains_key(&coords))
+    });
+    for (coords, surface) in chained_walls {
+        let corner = Vec2::new(coords.x as f32, coords.y as f32);
+        // the neighbour to check and the face it shares with this wall
+        let faces = [
+            (GridCoords::new(0, 1), Vec2::new(0., 1.), Vec2::new(1., 1.)),
+            (GridCoords::new(0, -1), Vec2::new(0., 0.), Vec2::new(1., 0.)),
+            (GridCoords::new(-1, 0), Vec2::new(0., 0.), Vec2::new(0., 1.)),
+            (GridCoords::new(1, 0), Vec2::new(1., 0.), Vec2::new(1., 1.)),
+        ];
+        for (offset, start, end) in faces {
+            if !is_solid(&(*coords + offset)) {
+                pieces
+                    .entry(*surface)
+                    .or_default()
+                    .push(vec![corner + start, corner + end]);
+            }
+        }
+    }
+
+    pieces
+}
+
+/// Joins polylines that share end points into longer chains.
+fn chain_pieces(pieces: Vec<Vec<Vec2>>) -> Vec<Vec<Vec2>> {
+    // end points are compared at a quarter pixel precision
+    let key = |point: &Vec2| (*point * 4.).round().as_ivec2();
+
+    let mut ends: HashMap<IVec2, Vec<usize>> = HashMap::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        ends.entry(key(&piece[0])).or_default().push(i);
+        ends.entry(key(&piece[piece.len() - 1]))
+            .or_default()
+            .push(i);
+    }
+
+    let is_open_end = |point: &Vec2| ends[&key(point)].len() == 1;
+
+    // start with pieces at the open ends of chains, so they aren't split in the middle,
+    // the rest are closed loops
+    let mut order: Vec<usize> = (0..pieces.len()).collect();
+    order.sort_by_key(|&i| {
+        !(is_open_end(&pieces[i][0]) || is_open_end(&pieces[i][pieces[i].len() - 1]))
+    });
+
+    let mut used = vec![false; pieces.len()];
+    let mut chains = Vec::new();
+
+    for start in order {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let mut chain = pieces[start].clone();
+        if is_open_end(&chain[chain.len() - 1]) {
+            chain.reverse();
+        }
+
+        // extend forwards, then turn around and extend the other end
+        for _ in 0..2 {
+            while let Some(next) = ends[&key(&chain[chain.len() - 1])]
+                .iter()
+                .copied()
+                .find(|&i| !used[i])
+            {
+                used[next] = true;
+                let mut piece = pieces[next].clone();
+                if key(&piece[0]) != key(&chain[chain.len() - 1]) {
+                    piece.reverse();
+                }
+                chain.extend(piece.into_iter().skip(1));
+            }
+            chain.reverse();
+        }
+
+        chains.push(remove_straight_vertices(chain));
+    }
+
+    chains
+}
+
+/// Removes vertices that lie on a straight line between their neighbours.
+fn remove_straight_vertices(chain: Vec<Vec2>) -> Vec<Vec2> {
+    let mut simplified: Vec<Vec2> = Vec::with_capacity(chain.len());
+    for point in chain {
+        if let [.., a, b] = simplified[..] {
+            let (ab, bp) = (b - a, point - b);
+            if ab.normalize().perp_dot(bp.normalize()).abs() < 1e-4 && ab.dot(bp) > 0. {
+                simplified.pop();
+            }
+        }
+        simplified.push(point);
+    }
+    simplified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(from: (f32, f32), to: (f32, f32)) -> Vec<Vec2> {
+        vec![Vec2::new(from.0, from.1), Vec2::new(to.0, to.1)]
+    }
+
+    #[test]
+    fn straight_vertices_are_removed() {
+        let chain = vec![
+            Vec2::new(0., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(2., 1.),
+            Vec2::new(2., 3.),
+        ];
+        assert_eq!(
+            remove_straight_vertices(chain),
+            vec![Vec2::new(0., 0.), Vec2::new(2., 0.), Vec2::new(2., 3.)]
+        );
+    }
+
+    #[test]
+    fn turning_back_is_not_straight() {
+        let chain = vec![Vec2::new(0., 0.), Vec2::new(2., 0.), Vec2::new(1., 0.)];
+        assert_eq!(remove_straight_vertices(chain.clone()), chain);
+    }
+
+    #[test]
+    fn pieces_are_chained_whatever_their_direction() {
+        let chains = chain_pieces(vec![
+            segment((0., 0.), (1., 0.)),
+            segment((2., 1.), (1., 0.)),
+        ]);
+        assert_eq!(chains.len(), 1);
+        let chain = &chains[0];
+        assert_eq!(chain.len(), 3);
+        let ends = [chain[0], chain[2]];
+        assert!(ends.contains(&Vec2::new(0., 0.)) && ends.contains(&Vec2::new(2., 1.)));
+    }
+
+    #[test]
+    fn collinear_pieces_become_one_segment() {
+        let chains = chain_pieces(vec![
+            segment((1., 0.), (2., 0.)),
+            segment((0., 0.), (1., 0.)),
+            segment((2., 0.), (3., 0.)),
+        ]);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].len(), 2);
+    }
+
+    #[test]
+    fn chains_start_at_open_ends() {
+        // the middle piece comes first, but must not split the chain
+        let chains = chain_pieces(vec![
+            segment((1., 0.), (2., 1.)),
+            segment((0., 0.), (1., 0.)),
+            segment((2., 1.), (3., 1.)),
+        ]);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].len(), 4);
+    }
+
+    #[test]
+    fn closed_loops_end_where_they_start() {
+        let chains = chain_pieces(vec![
+            segment((0., 0.), (1., 0.)),
+            segment((1., 0.), (1., 1.)),
+            segment((1., 1.), (0., 1.)),
+            segment((0., 1.), (0., 0.)),
+        ]);
+        assert_eq!(chains.len(), 1);
+        let chain = &chains[0];
+        assert_eq!(chain.len(), 5);
+        assert_eq!(chain[0], chain[4]);
+    }
+
+    fn ramp(first_value: i32, corner: Corner) -> Ramp {
+        let (_, shape) = RAMP_VALUES
+            .into_iter()
+            .find(|(value, _)| *value == first_value)
+            .unwrap();
+        Ramp { shape, corner }
+    }
+
+    fn block(origin: GridCoords, size: IVec2, ramp: Ramp) -> Vec<(GridCoords, Ramp)> {
+        (0..size.x)
+            .flat_map(|x| (0..size.y).map(move |y| (origin + GridCoords::new(x, y), ramp)))
+            .collect()
+    }
+
+    #[test]
+    fn slopes_rise_at_their_angle() {
+        for (first_value, degrees) in [(21, 45_f32), (25, 22.5)] {
+            for corner in [
+                Corner::BottomRight,
+                Corner::BottomLeft,
+                Corner::TopRight,
+                Corner::TopLeft,
+            ] {
+                let surface = ramp(first_value, corner).surface();
+                let slope = surface
+                    .windows(2)
+                    .map(|pair| pair[1] - pair[0])
+                    .find(|step| step.y != 0.)
+                    .unwrap();
+                let angle = (slope.y / slope.x).abs().atan().to_degrees();
+                assert!((angle - degrees).abs() < 0.01, "{corner:?}: {angle}°");
+            }
+        }
+    }
+
+    #[test]
+    fn slopes_fill_whole_tiles() {
+        assert_eq!(ramp(21, Corner::BottomRight).size(), IVec2::new(1, 1));
+        // 2 / tan(22.5°) is about 4.83 tiles
+        assert_eq!(ramp(25, Corner::BottomRight).size(), IVec2::new(5, 2));
+        let surface = ramp(25, Corner::BottomRight).surface();
+        assert_eq!(surface.last(), Some(&Vec2::new(5., 2.)));
+    }
+
+    #[test]
+    fn ramps_are_split_at_their_size() {
+        let curve = ramp(13, Corner::BottomRight);
+        let slope = ramp(25, Corner::BottomLeft);
+        let tiles: HashMap<GridCoords, Ramp> = [
+            block(GridCoords::new(0, 0), IVec2::new(2, 2), curve),
+            // two curves of the same kind right next to each other
+            block(GridCoords::new(2, 0), IVec2::new(2, 2), curve),
+            block(GridCoords::new(0, 5), IVec2::new(5, 2), slope),
+        ]
+        .concat()
+        .into_iter()
+        .collect();
+
+        let mut origins = find_ramp_origins(&tiles);
+        origins.sort_by_key(|(coords, _)| (coords.x, coords.y));
+        assert_eq!(
+            origins,
+            vec![
+                (GridCoords::new(0, 0), curve),
+                (GridCoords::new(0, 5), slope),
+                (GridCoords::new(2, 0), curve),
+            ]
+        );
+    }
+
+    #[test]
+    fn ramps_missing_tiles_are_still_found() {
+        let curve = ramp(17, Corner::TopLeft);
+        let mut tiles: HashMap<GridCoords, Ramp> =
+            block(GridCoords::new(0, 0), IVec2::splat(3), curve)
+                .into_iter()
+                .collect();
+        tiles.remove(&GridCoords::new(2, 2));
+        assert_eq!(
+            find_ramp_origins(&tiles),
+            vec![(GridCoords::new(0, 0), curve)]
+        );
+    }
+
+    #[test]
+    fn separate_pieces_stay_separate() {
+        let chains = chain_pieces(vec![
+            segment((0., 0.), (1., 0.)),
+            segment((5., 0.), (6., 1.)),
+        ]);
+        assert_eq!(chains.len(), 2);
+    }
+
+    #[test]
+    fn walls_next_to_ramps_are_chained_with_their_own_surface() {
+        let ramps: HashMap<GridCoords, Ramp> =
+            [(GridCoords::new(1, 0), ramp(21, Corner::BottomRight))]
+                .into_iter()
+                .collect();
+        let walls: HashMap<GridCoords, Surface> = [
+            (GridCoords::new(2, 0), Surface::Stone),
+            (GridCoords::new(0, 0), Surface::Ice),
+            (GridCoords::new(5, 0), Surface::Mud),
+        ]
+        .into_iter()
+        .collect();
+
+        let pieces = surface_pieces(&ramps, &walls);
+        assert_eq!(pieces[&RAMP_SURFACE].len(), 1);
+        // the top of the stone wall continues the slope, its side facing the ramp is covered
+        assert!(pieces[&Surface::Stone].contains(&vec![Vec2::new(2., 1.), Vec2::new(3., 1.)]));
+        assert!(!pieces[&Surface::Stone].contains(&vec![Vec2::new(2., 0.), Vec2::new(2., 1.)]));
+        assert_eq!(pieces[&Surface::Ice].len(), 3);
+        // walls away from the ramps are merged with the other walls instead
+        assert!(!pieces.contains_key(&Surface::Mud));
+        assert!(!is_chained_wall(GridCoords::new(0, 0), true, |coords| {
+            ramps.contains_key(&coords)
+        }));
+    }
+}
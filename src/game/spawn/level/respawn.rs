@@ -0,0 +1,193 @@
+//! Player death and respawning.
+//!
+//! Touching a [`Hazard`] triggers [`PlayerDied`]. The wheel then plays a short
+//! death animation and is put back at the [`RespawnPoint`], which is moved
+//! by reaching checkpoints.
+//! Items picked up since then are put back in the level and taken from the player again.
+//! Items used up since then, like a bluberry that wore off, aren't given back.
+//! The [`LevelTimer`](super::LevelTimer) keeps running the whole time.
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::spawn::player::{Hamster, Player},
+    screen::Screen,
+};
+
+use super::{
+    components::Hazard,
+    items::{Collected, ItemType, Items},
+    shake::ScreenShake,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RespawnPoint>();
+    app.init_resource::<RespawnPoint>();
+    app.observe(on_player_died).observe(respawn_player);
+    app.add_systems(
+        Update,
+        (set_initial_respawn_point, kill_player_on_hazard, tick_dying)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), reset_respawn_point);
+}
+
+const DEATH_DURATION_SECS: f32 = 0.8;
+
+/// Where the player comes back after dying.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct RespawnPoint {
+    /// Local to the player's parent, like the player's own [`Transform`].
+    pub position: Option<Vec2>,
+    /// The items that were collected when the respawn point was set.
+    ///
+    /// Only which items, not the player's [`Items`]: the counts are worked out from the items
+    /// put back, so items used up since aren't given back.
+    #[reflect(ignore)]
+    pub collected: HashSet<Entity>,
+}
+
+/// Trigger this event to kill the player.
+#[derive(Event)]
+pub struct PlayerDied;
+
+/// Trigger this event to put the player back at the [`RespawnPoint`].
+#[derive(Event)]
+pub struct RespawnPlayer;
+
+/// Present on the player while the death animation plays.
+#[derive(Component)]
+pub struct Dying(Timer);
+
+// the spawn location from LDtk is the first respawn point
+fn set_initial_respawn_point(
+    mut respawn_point: ResMut<RespawnPoint>,
+    player: Query<&Transform, Added<Player>>,
+) {
+    if let Ok(transform) = player.get_single() {
+        if respawn_point.position.is_none() {
+            respawn_point.position = Some(transform.translation.truncate());
+        }
+    }
+}
+
+fn reset_respawn_point(mut respawn_point: ResMut<RespawnPoint>) {
+    *respawn_point = RespawnPoint::default();
+}
+
+fn kill_player_on_hazard(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    player: Query<(), (With<Player>, Without<Dying>)>,
+    hazards: Query<(), With<Hazard>>,
+) {
+    for collision in collisions.read() {
+        if let CollisionEvent::Started(a, b, _) = collision {
+            if (player.contains(*a) && hazards.contains(*b))
+                || (player.contains(*b) && hazards.contains(*a))
+            {
+                commands.trigger(PlayerDied);
+                // one death per frame is enough
+                return;
+            }
+        }
+    }
+}
+
+fn on_player_died(
+    _: Trigger<PlayerDied>,
+    mut commands: Commands,
+    mut player: Query<(Entity, &mut Velocity), (With<Player>, Without<Dying>)>,
+    mut hamster: Query<&mut Visibility, With<Hamster>>,
+    mut shake: ResMut<ScreenShake>,
+) {
+    let Ok((entity, mut velocity)) = player.get_single_mut() else {
+        return;
+    };
+    shake.add_trauma(0.7);
+
+    // freeze the wheel in place while the animation plays
+    *velocity = Velocity::zero();
+    commands.entity(entity).insert((
+        RigidBody::Fixed,
+        Dying(Timer::from_seconds(DEATH_DURATION_SECS, TimerMode::Once)),
+    ));
+
+    for mut visibility in &mut hamster {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+fn tick_dying(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut player: Query<(&mut Dying, &mut Sprite, &mut Transform)>,
+) {
+    for (mut dying, mut sprite, mut transform) in &mut player {
+        dying.0.tick(time.delta());
+
+        // fade out to red while spinning
+        let t = dying.0.fraction();
+        sprite.color = Color::srgba(1., 1. - t, 1. - t, 1. - t);
+        transform.rotate_z(-10. * time.delta_seconds());
+
+        if dying.0.just_finished() {
+            commands.trigger(RespawnPlayer);
+        }
+    }
+}
+
+fn respawn_player(
+    _: Trigger<RespawnPlayer>,
+    mut commands: Commands,
+    respawn_point: Res<RespawnPoint>,
+    mut player: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut Sprite,
+            &mut Items,
+        ),
+        With<Player>,
+    >,
+    mut hamster: Query<&mut Visibility, With<Hamster>>,
+    collected: Query<(Entity, &ItemType), With<Collected>>,
+) {
+    let Ok((entity, mut transform, mut velocity, mut sprite, mut items)) = player.get_single_mut()
+    else {
+        return;
+    };
+
+    for (item, item_type) in &collected {
+        if respawn_point.collected.contains(&item) {
+            continue;
+        }
+        if let Some(count) = items.0.get_mut(item_type) {
+            *count = count.saturating_sub(1);
+        }
+        commands
+            .entity(item)
+            .remove::<(Collected, ColliderDisabled)>()
+            .insert(Visibility::Inherited);
+    }
+
+    if let Some(position) = respawn_point.position {
+        transform.translation = position.extend(transform.translation.z);
+    }
+
+    transform.rotation = Quat::IDENTITY;
+    *velocity = Velocity::zero();
+    sprite.color = Color::WHITE;
+
+    commands
+        .entity(entity)
+        .insert(RigidBody::Dynamic)
+        .remove::<Dying>();
+
+    for mut visibility in &mut hamster {
+        *visibility = Visibility::Inherited;
+    }
+}
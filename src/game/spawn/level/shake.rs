@@ -0,0 +1,67 @@
+//! Shakes the camera on heavy impacts, unless screen shake is turned off in the settings.
+
+use bevy::prelude::*;
+
+use crate::{screen::Screen, settings::AccessibilitySettings};
+
+use super::spawn::camera_fit_inside_current_level;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ScreenShake>();
+    app.init_resource::<ScreenShake>();
+    app.add_systems(
+        Update,
+        shake_camera
+            .after(camera_fit_inside_current_level)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), reset_screen_shake);
+}
+
+/// Camera offset in pixels at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 6.;
+
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// How much the camera shakes, from 0 to 1.
+/// The offset grows with the square of the trauma, so small hits barely shake.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct ScreenShake {
+    trauma: f32,
+}
+
+impl ScreenShake {
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.);
+    }
+}
+
+// runs after the camera was moved to the player this frame, so the offset never adds up
+fn shake_camera(
+    time: Res<Time>,
+    settings: Res<AccessibilitySettings>,
+    mut shake: ResMut<ScreenShake>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+) {
+    if shake.trauma <= 0. {
+        return;
+    }
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.);
+    if !settings.screen_shake {
+        return;
+    }
+
+    // based on the game time, so the camera holds still while paused
+    let t = time.elapsed_seconds();
+    let offset =
+        Vec2::new((t * 61.).sin(), (t * 47. + 1.3).sin()) * MAX_SHAKE_OFFSET * shake.trauma.powi(2);
+    for mut transform in &mut camera {
+        transform.translation += offset.extend(0.);
+    }
+}
+
+fn reset_screen_shake(mut shake: ResMut<ScreenShake>) {
+    shake.trauma = 0.;
+}
@@ -0,0 +1,250 @@
+//! Switches and pressure plates, sending [`Signal`]s to the entities
+//! referenced by their `targets` field in LDtk.
+//!
+//! How the targets react:
+//! - doors open while the signal is active
+//! - moving platforms only move while the signal is active
+//! - hazards are turned off while the signal is active
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{game::spawn::player::Player, screen::Screen, settings::AccessibilitySettings};
+
+use super::{
+    components::{Hazard, SensorBundle},
+    palette::StatePalette,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_ldtk_entity::<SwitchBundle>("Switch");
+    app.register_ldtk_entity::<PressurePlateBundle>("PressurePlate");
+    app.observe(toggle_hazard);
+    app.add_systems(
+        Update,
+        (
+            send_initial_signals,
+            flip_switches,
+            press_plates,
+            color_signal_sources,
+        )
+            .chain()
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Sent to the targets of a [`SignalSource`] whenever it is turned on or off.
+#[derive(Event, Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Signal {
+    pub active: bool,
+}
+
+/// The iids of the entities referenced by an entity's `targets` field.
+pub fn target_iids(entity_instance: &EntityInstance) -> Vec<String> {
+    entity_instance
+        .iter_entity_refs_field("targets")
+        .expect("targets field should be correctly typed")
+        .map(|reference| reference.entity_iid.clone())
+        .collect()
+}
+
+/// Something sending [`Signal`]s to the entities with the given iids.
+#[derive(Clone, PartialEq, Debug, Default, Component)]
+pub struct SignalSource {
+    pub targets: Vec<String>,
+    pub active: bool,
+}
+
+impl From<&EntityInstance> for SignalSource {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        SignalSource {
+            targets: target_iids(entity_instance),
+            active: false,
+        }
+    }
+}
+
+/// Something reacting to [`Signal`]s, sent the current state of its sources when it is spawned.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct SignalTarget;
+
+/// Flips on or off every time the wheel touches it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct Switch;
+
+/// Active while something is on it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct PressurePlate;
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct SwitchBundle {
+    #[with(signal_source_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[from_entity_instance]
+    pub sensor_bundle: SensorBundle,
+    #[from_entity_instance]
+    pub signal_source: SignalSource,
+    pub switch: Switch,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct PressurePlateBundle {
+    #[with(signal_source_sprite)]
+    pub sprite_bundle: SpriteBundle,
+    #[from_entity_instance]
+    pub sensor_bundle: SensorBundle,
+    #[from_entity_instance]
+    pub signal_source: SignalSource,
+    pub pressure_plate: PressurePlate,
+}
+
+fn signal_source_sprite(entity_instance: &EntityInstance) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: StatePalette::NORMAL.inactive_signal,
+            custom_size: Some(Vec2::new(
+                entity_instance.width as f32,
+                entity_instance.height as f32,
+            )),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn entities_by_iid<'a>(entities: &'a Query<(Entity, &EntityIid)>) -> HashMap<&'a str, Entity> {
+    entities
+        .iter()
+        .map(|(entity, iid)| (iid.as_str(), entity))
+        .collect()
+}
+
+/// Sends a [`Signal`] to all targets of a source.
+fn send_signal(commands: &mut Commands, targets: &HashMap<&str, Entity>, source: &SignalSource) {
+    let entities: Vec<Entity> = source
+        .targets
+        .iter()
+        .filter_map(|iid| targets.get(iid.as_str()).copied())
+        .collect();
+    trigger_signal(commands, entities, source);
+}
+
+fn trigger_signal(commands: &mut Commands, entities: Vec<Entity>, source: &SignalSource) {
+    if !entities.is_empty() {
+        commands.trigger_targets(
+            Signal {
+                active: source.active,
+            },
+            entities,
+        );
+    }
+}
+
+// new sources tell all their targets their state, and every source tells its new targets,
+// so targets spawned after their sources (like those of another level) are in sync as well
+fn send_initial_signals(
+    mut commands: Commands,
+    sources: Query<Ref<SignalSource>>,
+    new_targets: Query<(Entity, &EntityIid), Added<SignalTarget>>,
+    entities: Query<(Entity, &EntityIid)>,
+) {
+    let new_targets: HashMap<&str, Entity> = new_targets
+        .iter()
+        .map(|(entity, iid)| (iid.as_str(), entity))
+        .collect();
+    let mut targets = None;
+    for source in &sources {
+        if source.is_added() {
+            let targets = targets.get_or_insert_with(|| entities_by_iid(&entities));
+            send_signal(&mut commands, targets, &source);
+        } else if !new_targets.is_empty() {
+            send_signal(&mut commands, &new_targets, &source);
+        }
+    }
+}
+
+fn flip_switches(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    player: Query<(), With<Player>>,
+    mut switches: Query<&mut SignalSource, With<Switch>>,
+    entities: Query<(Entity, &EntityIid)>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(a, b, _) = collision else {
+            continue;
+        };
+        let switch = if player.contains(*a) {
+            *b
+        } else if player.contains(*b) {
+            *a
+        } else {
+            continue;
+        };
+        let Ok(mut source) = switches.get_mut(switch) else {
+            continue;
+        };
+
+        source.active = !source.active;
+        send_signal(&mut commands, &entities_by_iid(&entities), &source);
+    }
+}
+
+// pressed by the wheel and by enemies, anything that falls
+fn press_plates(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    mut plates: Query<(Entity, &mut SignalSource), With<PressurePlate>>,
+    bodies: Query<&RigidBody>,
+    entities: Query<(Entity, &EntityIid)>,
+) {
+    for (plate, mut source) in &mut plates {
+        let pressed = rapier_context
+            .intersection_pairs_with(plate)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .any(|(a, b, _)| {
+                let other = if a == plate { b } else { a };
+                bodies.get(other) == Ok(&RigidBody::Dynamic)
+            });
+        if pressed == source.active {
+            continue;
+        }
+
+        source.active = pressed;
+        send_signal(&mut commands, &entities_by_iid(&entities), &source);
+    }
+}
+
+fn color_signal_sources(
+    settings: Res<AccessibilitySettings>,
+    mut sources: Query<(Ref<SignalSource>, &mut Sprite)>,
+) {
+    let palette = StatePalette::new(&settings);
+    for (source, mut sprite) in &mut sources {
+        if !source.is_changed() && !settings.is_changed() {
+            continue;
+        }
+        sprite.color = if source.active {
+            palette.active_signal
+        } else {
+            palette.inactive_signal
+        };
+    }
+}
+
+fn toggle_hazard(
+    trigger: Trigger<Signal>,
+    mut commands: Commands,
+    hazards: Query<(), With<Hazard>>,
+) {
+    let hazard = trigger.entity();
+    if !hazards.contains(hazard) {
+        return;
+    }
+    if trigger.event().active {
+        commands.entity(hazard).insert(ColliderDisabled);
+    } else {
+        commands.entity(hazard).remove::<ColliderDisabled>();
+    }
+}
@@ -1,11 +1,17 @@
 use crate::{
     game::spawn::player::{Player, PlayerBundle},
-    screen::Screen,
+    screen::{Pause, Screen},
 };
 
-use super::components::*;
+use super::{
+    components::*,
+    platforms::OneWayPlatformHooks,
+    ramps::{is_chained_wall, Ramp},
+    respawn::{Dying, RespawnPlayer},
+    surfaces::Surface,
+};
 use bevy::prelude::*;
-use bevy_ecs_ldtk::prelude::*;
+use bevy_ecs_ldtk::{ldtk::Level, prelude::*};
 
 use std::collections::{HashMap, HashSet};
 
@@ -14,7 +20,7 @@ use bevy_rapier2d::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         LdtkPlugin,
-        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0),
+        RapierPhysicsPlugin::<OneWayPlatformHooks>::pixels_per_meter(100.0),
     ))
     .insert_resource(RapierConfiguration {
         // TODO
@@ -29,6 +35,7 @@ pub(super) fn plugin(app: &mut App) {
         scaled_shape_subdivision: 10,
         force_update_from_transform_changes: false,
     })
+    .init_resource::<WallColliderCache>()
     .insert_resource(LevelSelection::Uid(0))
     .insert_resource(LdtkSettings {
         level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
@@ -37,26 +44,182 @@ pub(super) fn plugin(app: &mut App) {
         set_clear_color: SetClearColor::FromLevelBackground,
         ..Default::default()
     })
-    .add_systems(Update, spawn_wall_collision)
     .add_systems(
         Update,
         (
-            camera_fit_inside_current_level,
-            update_level_selection,
-            restart_level,
+            clear_wall_collider_cache,
+            spawn_wall_collision.pipe(log_level_error),
+        )
+            .chain(),
+    )
+    .add_systems(
+        Update,
+        (
+            camera_fit_inside_current_level.pipe(log_level_errors),
+            update_level_selection.pipe(log_level_error),
+            restart_level.run_if(in_state(Pause::Running)),
         )
             .run_if(in_state(Screen::Playing)),
     )
     .register_ldtk_int_cell::<WallBundle>(1)
     .register_ldtk_int_cell::<WallBundle>(3)
+    .register_ldtk_int_cell::<WallBundle>(10)
+    .register_ldtk_int_cell::<WallBundle>(11)
+    .register_ldtk_int_cell::<WallBundle>(12)
+    .register_ldtk_int_cell::<HazardBundle>(HAZARD_VALUE)
+    .register_ldtk_int_cell::<OneWayPlatformBundle>(9)
+    .register_ldtk_entity::<HazardEntityBundle>("Hazard")
     .register_ldtk_entity::<PlayerBundle>("Player");
+    // levels are spawned after `Update`, the tiles need their collider before the physics step
+    app.add_systems(
+        PostUpdate,
+        size_hazard_tiles.before(PhysicsSet::SyncBackend),
+    );
+}
+
+// hazard entities are sized in LDtk and spawned with their collider
+fn size_hazard_tiles(
+    mut commands: Commands,
+    tiles: Query<(Entity, &Parent), (Added<Hazard>, Without<Collider>)>,
+    layers: Query<&LayerMetadata>,
+) {
+    for (tile, layer) in &tiles {
+        let Ok(layer) = layers.get(layer.get()) else {
+            continue;
+        };
+        let half_size = layer.grid_size as f32 / 2. * HAZARD_TILE_COVERAGE;
+        commands
+            .entity(tile)
+            .insert(Collider::cuboid(half_size, half_size));
+    }
+}
+
+/// Identifier of the IntGrid layer the walls are painted on.
+const COLLISIONS_LAYER: &str = "Collisions";
+
+/// Why the LDtk data of a spawned level could not be used.
+#[derive(Debug)]
+pub enum LevelError {
+    /// The level isn't part of an LDtk world with a loaded project.
+    ProjectNotLoaded,
+    LevelNotFound(String),
+    /// The level has no IntGrid layer called [`COLLISIONS_LAYER`].
+    LayerNotFound(String),
+}
+
+impl std::fmt::Display for LevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LevelError::ProjectNotLoaded => {
+                write!(f, "the LDtk project of a spawned level isn't loaded")
+            }
+            LevelError::LevelNotFound(iid) => {
+                write!(f, "spawned level {iid} doesn't exist in its LDtk project")
+            }
+            LevelError::LayerNotFound(iid) => {
+                write!(f, "level {iid} has no \"{COLLISIONS_LAYER}\" layer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+/// Logs the errors returned by the level systems.
+/// Most of them run every frame, so every distinct error is only logged once.
+fn log_level_error(In(result): In<Result<(), LevelError>>, mut logged: Local<HashSet<String>>) {
+    if let Err(err) = result {
+        log_once(err, &mut logged);
+    }
+}
+
+/// Logs the errors of the level systems that skip the levels they fail on.
+fn log_level_errors(In(errors): In<Vec<LevelError>>, mut logged: Local<HashSet<String>>) {
+    for err in errors {
+        log_once(err, &mut logged);
+    }
+}
+
+fn log_once(err: LevelError, logged: &mut HashSet<String>) {
+    let message = err.to_string();
+    if logged.insert(message.clone()) {
+        error!("{message}");
+    }
+}
+
+/// The LDtk data of a spawned level, looked up in the project of its world.
+fn raw_level<'a>(
+    level_iid: &LevelIid,
+    world: &Parent,
+    ldtk_projects: &Query<&Handle<LdtkProject>>,
+    ldtk_project_assets: &'a Assets<LdtkProject>,
+) -> Result<&'a Level, LevelError> {
+    ldtk_projects
+        .get(world.get())
+        .ok()
+        .and_then(|handle| ldtk_project_assets.get(handle))
+        .ok_or(LevelError::ProjectNotLoaded)?
+        .get_raw_level_by_iid(&level_iid.to_string())
+        .ok_or_else(|| LevelError::LevelNotFound(level_iid.to_string()))
+}
+
+/// Marks the merged collider of some walls, a child of their level.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct WallCollider;
 
-    // register ramp bundles
-    for i in 4..=8 {
-        app.register_ldtk_int_cell::<RampBundle>(i);
+/// The surface of the walls and whether they are one-way platforms.
+/// Walls are only merged with walls of the same kind, so every collider has one material.
+type WallKind = (Surface, bool);
+
+/// The merged walls of every level that was spawned, by level iid,
+/// so a level that is spawned again doesn't have to merge its walls again.
+/// Cleared whenever the LDtk project changes.
+#[derive(Resource, Default)]
+pub struct WallColliderCache(HashMap<String, MergedWalls>);
+
+// a hot-reloaded project may have levels of a different size with the same walls
+fn clear_wall_collider_cache(
+    mut project_events: EventReader<AssetEvent<LdtkProject>>,
+    mut cache: ResMut<WallColliderCache>,
+) {
+    // read every event, so they don't pile up for the next frame
+    let changed = project_events
+        .read()
+        .filter(|event| {
+            matches!(
+                event,
+                AssetEvent::Modified { .. } | AssetEvent::Removed { .. }
+            )
+        })
+        .count();
+    if changed > 0 {
+        cache.0.clear();
     }
 }
 
+pub struct MergedWalls {
+    walls: HashMap<WallKind, HashSet<GridCoords>>,
+    grid_size: i32,
+    rects: Vec<(WallKind, WallRect)>,
+}
+
+/// Represents a wide wall that is 1 tile tall
+/// Used to spawn wall collisions
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
+struct Plate {
+    left: i32,
+    right: i32,
+}
+
+/// A simple rectangle type representing a wall of any size
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct WallRect {
+    left: i32,
+    right: i32,
+    top: i32,
+    bottom: i32,
+}
+
 /// Spawns heron collisions for the walls of a level
 ///
 /// You could just insert a ColliderBundle in to the WallBundle,
@@ -66,169 +229,227 @@ pub(super) fn plugin(app: &mut App) {
 /// Instead, by flagging the wall tiles and spawning the collisions later,
 /// we can minimize the amount of colliding entities.
 ///
-/// The algorithm used here is a nice compromise between simplicity, speed,
-/// and a small number of rectangle colliders.
-/// In basic terms, it will:
-/// 1. consider where the walls are
-/// 2. combine wall tiles into flat "plates" in each individual row
-/// 3. combine the plates into rectangles across multiple rows wherever possible
-/// 4. spawn colliders for each rectangle
+/// Whenever walls are added to a level, all colliders of that level are replaced,
+/// so levels never end up with duplicate colliders.
+/// The merged rectangles are cached by level iid and reused when the same level
+/// is spawned again with the same walls.
+///
+/// Walls chained with a ramp are left out, the ramp colliders cover them,
+/// see [`spawn_ramp_collision`](super::ramps::spawn_ramp_collision).
 pub(super) fn spawn_wall_collision(
     mut commands: Commands,
-    wall_query: Query<(&GridCoords, &Parent), Added<Wall>>,
+    mut cache: ResMut<WallColliderCache>,
+    added_walls: Query<&Parent, Added<Wall>>,
+    wall_query: Query<(&GridCoords, &Parent, Option<&Surface>, Has<OneWayPlatform>), With<Wall>>,
+    ramp_query: Query<(&GridCoords, &Parent), With<Ramp>>,
     parent_query: Query<&Parent, Without<Wall>>,
-    level_query: Query<(Entity, &LevelIid)>,
+    level_query: Query<(&LevelIid, &Parent, Option<&Children>)>,
+    wall_colliders: Query<(), With<WallCollider>>,
     ldtk_projects: Query<&Handle<LdtkProject>>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
-) {
-    /// Represents a wide wall that is 1 tile tall
-    /// Used to spawn wall collisions
-    #[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
-    struct Plate {
-        left: i32,
-        right: i32,
-    }
-
-    /// A simple rectangle type representing a wall of any size
-    struct Rect {
-        left: i32,
-        right: i32,
-        top: i32,
-        bottom: i32,
+) -> Result<(), LevelError> {
+    // An intgrid tile's direct parent will be a layer entity, not the level entity
+    // To get the level entity, you need the tile's grandparent.
+    // This is where parent_query comes in.
+    let level_of = |parent: &Parent| {
+        parent_query
+            .get(parent.get())
+            .ok()
+            .map(|grandparent| grandparent.get())
+    };
+
+    let changed_levels: HashSet<Entity> = added_walls.iter().filter_map(level_of).collect();
+    if changed_levels.is_empty() {
+        return Ok(());
     }
 
     // Consider where the walls are
     // storing them as GridCoords in a HashSet for quick, easy lookup
     //
-    // The key of this map will be the entity of the level the wall belongs to.
-    // This has two consequences in the resulting collision entities:
+    // The walls are split by level, which has two consequences in the resulting collision entities:
     // 1. it forces the walls to be split along level boundaries
     // 2. it lets us easily add the collision entities as children of the appropriate level entity
-    let mut level_to_wall_locations: HashMap<Entity, HashSet<GridCoords>> = HashMap::new();
-
-    wall_query.iter().for_each(|(&grid_coords, parent)| {
-        // An intgrid tile's direct parent will be a layer entity, not the level entity
-        // To get the level entity, you need the tile's grandparent.
-        // This is where parent_query comes in.
-        if let Ok(grandparent) = parent_query.get(parent.get()) {
-            level_to_wall_locations
-                .entry(grandparent.get())
-                .or_default()
-                .insert(grid_coords);
+    let mut level_to_wall_locations: HashMap<Entity, HashMap<WallKind, HashSet<GridCoords>>> =
+        HashMap::new();
+
+    // ramps by layer, the walls chained with them get their colliders from the ramps
+    let ramp_tiles: HashSet<(Entity, GridCoords)> = ramp_query
+        .iter()
+        .map(|(&coords, parent)| (parent.get(), coords))
+        .collect();
+
+    for (&grid_coords, parent, surface, one_way) in &wall_query {
+        let Some(level) = level_of(parent).filter(|level| changed_levels.contains(level)) else {
+            continue;
+        };
+        // every changed level gets an entry, even if all its walls are chained
+        let level_walls = level_to_wall_locations.entry(level).or_default();
+        let surface = surface.copied().unwrap_or_default();
+        if is_chained_wall(grid_coords, one_way, |coords| {
+            ramp_tiles.contains(&(parent.get(), coords))
+        }) {
+            continue;
         }
-    });
-
-    if !wall_query.is_empty() {
-        level_query.iter().for_each(|(level_entity, level_iid)| {
-            if let Some(level_walls) = level_to_wall_locations.get(&level_entity) {
-                let ldtk_project = ldtk_project_assets
-                    .get(ldtk_projects.single())
-                    .expect("Project should be loaded if level has spawned");
-
-                let level = ldtk_project
-                    .as_standalone()
-                    .get_loaded_level_by_iid(&level_iid.to_string())
-                    .expect("Spawned level should exist in LDtk project");
-
-                let LayerInstance {
-                    c_wid: width,
-                    c_hei: height,
-                    grid_size,
-                    ..
-                } = level.layer_instances()[0];
-
-                // combine wall tiles into flat "plates" in each individual row
-                let mut plate_stack: Vec<Vec<Plate>> = Vec::new();
-
-                for y in 0..height {
-                    let mut row_plates: Vec<Plate> = Vec::new();
-                    let mut plate_start = None;
-
-                    // + 1 to the width so the algorithm "terminates" plates that touch the right edge
-                    for x in 0..width + 1 {
-                        match (plate_start, level_walls.contains(&GridCoords { x, y })) {
-                            (Some(s), false) => {
-                                row_plates.push(Plate {
-                                    left: s,
-                                    right: x - 1,
-                                });
-                                plate_start = None;
-                            }
-                            (None, true) => plate_start = Some(x),
-                            _ => (),
-                        }
-                    }
+        level_walls
+            .entry((surface, one_way))
+            .or_default()
+            .insert(grid_coords);
+    }
+
+    // the other levels still get their colliders if one of them fails
+    let mut result = Ok(());
+
+    for (level_entity, walls) in level_to_wall_locations {
+        let Ok((level_iid, world, children)) = level_query.get(level_entity) else {
+            continue;
+        };
 
-                    plate_stack.push(row_plates);
+        let merged = match cache.0.remove(level_iid.as_str()) {
+            Some(merged) if merged.walls == walls => merged,
+            _ => {
+                let layer = raw_level(level_iid, world, &ldtk_projects, &ldtk_project_assets)
+                    .and_then(collisions_layer);
+                match layer {
+                    Ok(layer) => merge_walls(walls, layer.c_wid, layer.c_hei, layer.grid_size),
+                    Err(err) => {
+                        result = Err(err);
+                        continue;
+                    }
                 }
+            }
+        };
+
+        // the colliders of walls that were added earlier are replaced
+        for &child in children.into_iter().flatten() {
+            if wall_colliders.contains(child) {
+                commands.entity(child).despawn_recursive();
+            }
+        }
 
-                // combine "plates" into rectangles across multiple rows
-                let mut rect_builder: HashMap<Plate, Rect> = HashMap::new();
-                let mut prev_row: Vec<Plate> = Vec::new();
-                let mut wall_rects: Vec<Rect> = Vec::new();
-
-                // an extra empty row so the algorithm "finishes" the rects that touch the top edge
-                plate_stack.push(Vec::new());
-
-                for (y, current_row) in plate_stack.into_iter().enumerate() {
-                    for prev_plate in &prev_row {
-                        if !current_row.contains(prev_plate) {
-                            // remove the finished rect so that the same plate in the future starts a new rect
-                            if let Some(rect) = rect_builder.remove(prev_plate) {
-                                wall_rects.push(rect);
-                            }
-                        }
+        let grid_size = merged.grid_size as f32;
+        commands
+            .entity(level_entity)
+            // will be despawned when Playing is exited
+            .insert(StateScoped(Screen::Playing))
+            .with_children(|level| {
+                // Spawn colliders for every rectangle..
+                // Making the collider a child of the level serves two purposes:
+                // 1. Adjusts the transforms to be relative to the level for free
+                // 2. the colliders will be despawned automatically when levels unload
+                for &((surface, one_way), wall_rect) in &merged.rects {
+                    let mut wall = level.spawn(WallCollider);
+                    if one_way {
+                        wall.insert((OneWayPlatform, ActiveHooks::MODIFY_SOLVER_CONTACTS));
                     }
-                    for plate in &current_row {
-                        rect_builder
-                            .entry(plate.clone())
-                            .and_modify(|e| e.top += 1)
-                            .or_insert(Rect {
-                                bottom: y as i32,
-                                top: y as i32,
-                                left: plate.left,
-                                right: plate.right,
-                            });
+                    wall.insert(Collider::cuboid(
+                        (wall_rect.right as f32 - wall_rect.left as f32 + 1.) * grid_size / 2.,
+                        (wall_rect.top as f32 - wall_rect.bottom as f32 + 1.) * grid_size / 2.,
+                    ))
+                    .insert(RigidBody::Fixed)
+                    .insert(surface)
+                    .insert(surface.friction())
+                    .insert(surface.restitution())
+                    .insert(Transform::from_xyz(
+                        (wall_rect.left + wall_rect.right + 1) as f32 * grid_size / 2.,
+                        (wall_rect.bottom + wall_rect.top + 1) as f32 * grid_size / 2.,
+                        0.,
+                    ))
+                    .insert(GlobalTransform::default());
+                }
+            });
+
+        cache.0.insert(level_iid.to_string(), merged);
+    }
+
+    result
+}
+
+/// Finds the layer the walls of a level are painted on.
+fn collisions_layer(level: &Level) -> Result<&LayerInstance, LevelError> {
+    level
+        .layer_instances
+        .iter()
+        .flatten()
+        .find(|layer| layer.identifier == COLLISIONS_LAYER)
+        .ok_or_else(|| LevelError::LayerNotFound(level.iid.clone()))
+}
+
+/// The algorithm used here is a nice compromise between simplicity, speed,
+/// and a small number of rectangle colliders.
+/// In basic terms, it will:
+/// 1. combine wall tiles into flat "plates" in each individual row
+/// 2. combine the plates into rectangles across multiple rows wherever possible
+///
+/// `width` and `height` are the size of the layer in tiles.
+fn merge_walls(
+    walls: HashMap<WallKind, HashSet<GridCoords>>,
+    width: i32,
+    height: i32,
+    grid_size: i32,
+) -> MergedWalls {
+    let mut rects = Vec::new();
+
+    for (&kind, level_walls) in &walls {
+        // combine wall tiles into flat "plates" in each individual row
+        let mut plate_stack: Vec<Vec<Plate>> = Vec::new();
+
+        for y in 0..height {
+            let mut row_plates: Vec<Plate> = Vec::new();
+            let mut plate_start = None;
+
+            // + 1 to the width so the algorithm "terminates" plates that touch the right edge
+            for x in 0..width + 1 {
+                match (plate_start, level_walls.contains(&GridCoords { x, y })) {
+                    (Some(s), false) => {
+                        row_plates.push(Plate {
+                            left: s,
+                            right: x - 1,
+                        });
+                        plate_start = None;
                     }
-                    prev_row = current_row;
+                    (None, true) => plate_start = Some(x),
+                    _ => (),
                 }
+            }
+
+            plate_stack.push(row_plates);
+        }
+
+        // combine "plates" into rectangles across multiple rows
+        let mut rect_builder: HashMap<Plate, WallRect> = HashMap::new();
+        let mut prev_row: Vec<Plate> = Vec::new();
 
-                commands
-                    .entity(level_entity)
-                    // will be despawned when Playing is exited
-                    .insert(StateScoped(Screen::Playing))
-                    .with_children(|level| {
-                        // Spawn colliders for every rectangle..
-                        // Making the collider a child of the level serves two purposes:
-                        // 1. Adjusts the transforms to be relative to the level for free
-                        // 2. the colliders will be despawned automatically when levels unload
-                        for wall_rect in wall_rects {
-                            level
-                                .spawn_empty()
-                                .insert(Collider::cuboid(
-                                    (wall_rect.right as f32 - wall_rect.left as f32 + 1.)
-                                        * grid_size as f32
-                                        / 2.,
-                                    (wall_rect.top as f32 - wall_rect.bottom as f32 + 1.)
-                                        * grid_size as f32
-                                        / 2.,
-                                ))
-                                .insert(RigidBody::Fixed)
-                                .insert(Friction::new(1.0))
-                                .insert(Transform::from_xyz(
-                                    (wall_rect.left + wall_rect.right + 1) as f32
-                                        * grid_size as f32
-                                        / 2.,
-                                    (wall_rect.bottom + wall_rect.top + 1) as f32
-                                        * grid_size as f32
-                                        / 2.,
-                                    0.,
-                                ))
-                                .insert(GlobalTransform::default());
-                        }
+        // an extra empty row so the algorithm "finishes" the rects that touch the top edge
+        plate_stack.push(Vec::new());
+
+        for (y, current_row) in plate_stack.into_iter().enumerate() {
+            for prev_plate in &prev_row {
+                if !current_row.contains(prev_plate) {
+                    // remove the finished rect so that the same plate in the future starts a new rect
+                    if let Some(rect) = rect_builder.remove(prev_plate) {
+                        rects.push((kind, rect));
+                    }
+                }
+            }
+            for plate in &current_row {
+                rect_builder
+                    .entry(plate.clone())
+                    .and_modify(|e| e.top += 1)
+                    .or_insert(WallRect {
+                        bottom: y as i32,
+                        top: y as i32,
+                        left: plate.left,
+                        right: plate.right,
                     });
             }
-        });
+            prev_row = current_row;
+        }
+    }
+
+    MergedWalls {
+        walls,
+        grid_size,
+        rects,
     }
 }
 
@@ -243,11 +464,15 @@ pub fn camera_fit_inside_current_level(
         Without<Player>,
     >,
     player_query: Query<&Transform, With<Player>>,
-    level_query: Query<(&Transform, &LevelIid), (Without<OrthographicProjection>, Without<Player>)>,
+    level_query: Query<
+        (&Transform, &LevelIid, &Parent),
+        (Without<OrthographicProjection>, Without<Player>),
+    >,
     ldtk_projects: Query<&Handle<LdtkProject>>,
     level_selection: Res<LevelSelection>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
-) {
+) -> Vec<LevelError> {
+    let mut errors = Vec::new();
     if let Ok(Transform {
         translation: player_translation,
         ..
@@ -255,16 +480,21 @@ pub fn camera_fit_inside_current_level(
     {
         let player_translation = *player_translation;
 
-        let (mut orthographic_projection, mut camera_transform) = camera_query.single_mut();
-
-        for (level_transform, level_iid) in &level_query {
-            let ldtk_project = ldtk_project_assets
-                .get(ldtk_projects.single())
-                .expect("Project should be loaded if level has spawned");
+        // the camera may not be spawned yet
+        let Ok((mut orthographic_projection, mut camera_transform)) = camera_query.get_single_mut()
+        else {
+            return errors;
+        };
 
-            let level = ldtk_project
-                .get_raw_level_by_iid(&level_iid.to_string())
-                .expect("Spawned level should exist in LDtk project");
+        for (level_transform, level_iid, world) in &level_query {
+            // one broken level doesn't keep the camera from fitting the others
+            let level = match raw_level(level_iid, world, &ldtk_projects, &ldtk_project_assets) {
+                Ok(level) => level,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                }
+            };
 
             if level_selection.is_match(&LevelIndices::default(), level) {
                 let level_ratio = level.px_wid as f32 / level.px_hei as f32;
@@ -296,23 +526,18 @@ pub fn camera_fit_inside_current_level(
             }
         }
     }
+    errors
 }
 
 pub fn update_level_selection(
-    level_query: Query<(&LevelIid, &Transform), Without<Player>>,
+    level_query: Query<(&LevelIid, &Transform, &Parent), Without<Player>>,
     player_query: Query<&Transform, With<Player>>,
     mut level_selection: ResMut<LevelSelection>,
     ldtk_projects: Query<&Handle<LdtkProject>>,
     ldtk_project_assets: Res<Assets<LdtkProject>>,
-) {
-    for (level_iid, level_transform) in &level_query {
-        let ldtk_project = ldtk_project_assets
-            .get(ldtk_projects.single())
-            .expect("Project should be loaded if level has spawned");
-
-        let level = ldtk_project
-            .get_raw_level_by_iid(&level_iid.to_string())
-            .expect("Spawned level should exist in LDtk project");
+) -> Result<(), LevelError> {
+    for (level_iid, level_transform, world) in &level_query {
+        let level = raw_level(level_iid, world, &ldtk_projects, &ldtk_project_assets)?;
 
         let level_bounds = Rect {
             min: Vec2::new(level_transform.translation.x, level_transform.translation.y),
@@ -333,16 +558,90 @@ pub fn update_level_selection(
             }
         }
     }
+    Ok(())
 }
 
+// puts the player back at the latest checkpoint, collected items and the level timer are kept
+// the death animation respawns the player by itself
 pub fn restart_level(
     mut commands: Commands,
-    level_query: Query<Entity, With<LevelIid>>,
     input: Res<ButtonInput<KeyCode>>,
+    dying: Query<(), With<Dying>>,
 ) {
-    if input.just_pressed(KeyCode::KeyR) {
-        for level_entity in &level_query {
-            commands.entity(level_entity).insert(Respawn);
+    if input.just_pressed(KeyCode::KeyR) && dying.is_empty() {
+        commands.trigger(RespawnPlayer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiles(coords: &[(i32, i32)]) -> HashSet<GridCoords> {
+        coords.iter().map(|&(x, y)| GridCoords { x, y }).collect()
+    }
+
+    fn rect(left: i32, right: i32, bottom: i32, top: i32) -> WallRect {
+        WallRect {
+            left,
+            right,
+            top,
+            bottom,
         }
     }
+
+    fn sorted_rects(merged: MergedWalls) -> Vec<(WallKind, WallRect)> {
+        let mut rects = merged.rects;
+        rects.sort_by_key(|(_, rect)| (rect.bottom, rect.left));
+        rects
+    }
+
+    const DIRT: WallKind = (Surface::Dirt, false);
+    const ICE: WallKind = (Surface::Ice, false);
+
+    #[test]
+    fn block_is_merged_into_one_rect() {
+        let walls = HashMap::from([(DIRT, tiles(&[(0, 0), (1, 0), (0, 1), (1, 1)]))]);
+        assert_eq!(
+            sorted_rects(merge_walls(walls, 4, 4, 16)),
+            vec![(DIRT, rect(0, 1, 0, 1))]
+        );
+    }
+
+    #[test]
+    fn rows_of_different_width_are_separate_rects() {
+        // an L shape
+        let walls = HashMap::from([(DIRT, tiles(&[(0, 0), (1, 0), (2, 0), (0, 1), (0, 2)]))]);
+        assert_eq!(
+            sorted_rects(merge_walls(walls, 4, 4, 16)),
+            vec![(DIRT, rect(0, 2, 0, 0)), (DIRT, rect(0, 0, 1, 2))]
+        );
+    }
+
+    #[test]
+    fn surfaces_are_not_merged() {
+        let walls = HashMap::from([(DIRT, tiles(&[(0, 0)])), (ICE, tiles(&[(1, 0)]))]);
+        assert_eq!(
+            sorted_rects(merge_walls(walls, 4, 4, 16)),
+            vec![(DIRT, rect(0, 0, 0, 0)), (ICE, rect(1, 1, 0, 0))]
+        );
+    }
+
+    #[test]
+    fn rects_touching_the_edges_are_finished() {
+        let walls = HashMap::from([(DIRT, tiles(&[(2, 2), (3, 2), (2, 3), (3, 3)]))]);
+        assert_eq!(
+            sorted_rects(merge_walls(walls, 4, 4, 16)),
+            vec![(DIRT, rect(2, 3, 2, 3))]
+        );
+    }
+
+    #[test]
+    fn same_plate_after_a_gap_starts_a_new_rect() {
+        let walls = HashMap::from([(DIRT, tiles(&[(0, 0), (0, 2)]))]);
+        assert_eq!(
+            sorted_rects(merge_walls(walls, 4, 4, 16)),
+            vec![(DIRT, rect(0, 0, 0, 0)), (DIRT, rect(0, 0, 2, 2))]
+        );
+    }
 }
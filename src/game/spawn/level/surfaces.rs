@@ -0,0 +1,231 @@
+//! Wall materials and how the wheel reacts to rolling over them.
+
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{
+        assets::{HandleMap, SfxKey},
+        audio::sfx::PauseWithGame,
+        spawn::player::Player,
+    },
+    screen::Screen,
+    settings::AudioSettings,
+    AppSet,
+};
+
+use super::components::PLAYER_LINEAR_DAMPING;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Surface>();
+    app.register_type::<PlayerSurface>();
+    app.init_resource::<PlayerSurface>();
+    app.add_systems(
+        Update,
+        (
+            detect_player_surface.in_set(AppSet::RecordInput),
+            spawn_rolling_sound,
+            (apply_surface_damping, update_rolling_sound).in_set(AppSet::Update),
+        )
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Speed at which the rolling sound plays at full volume and its normal pitch.
+const ROLLING_SOUND_FULL_SPEED: f32 = 300.;
+
+/// How fast the rolling sound's volume follows the speed, per second.
+const ROLLING_SOUND_RESPONSE: f32 = 10.;
+
+/// The material of a wall, set by its IntGrid value.
+/// Walls are only merged with walls of the same surface.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Component, Reflect)]
+#[reflect(Component)]
+pub enum Surface {
+    #[default]
+    Dirt,
+    Stone,
+    Ice,
+    Mud,
+    BouncePad,
+}
+
+impl From<IntGridCell> for Surface {
+    fn from(int_grid_cell: IntGridCell) -> Self {
+        match int_grid_cell.value {
+            3 => Surface::Stone,
+            10 => Surface::Ice,
+            11 => Surface::Mud,
+            12 => Surface::BouncePad,
+            _ => Surface::Dirt,
+        }
+    }
+}
+
+impl Surface {
+    pub fn friction(&self) -> Friction {
+        match self {
+            Surface::Dirt | Surface::Stone | Surface::BouncePad => Friction::new(1.0),
+            // Min wins over the wheel's Average, so the ice stays slippery
+            Surface::Ice => Friction {
+                coefficient: 0.02,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            Surface::Mud => Friction {
+                coefficient: 2.0,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+        }
+    }
+
+    pub fn restitution(&self) -> Restitution {
+        match self {
+            // Max wins over the wheel's Multiply, so the pad bounces harder than the wheel would
+            Surface::BouncePad => Restitution {
+                coefficient: 1.1,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+            _ => Restitution::default(),
+        }
+    }
+
+    /// Extra linear damping of the wheel while rolling on this surface.
+    pub fn damping(&self) -> f32 {
+        match self {
+            Surface::Mud => 4.0,
+            Surface::Ice => -1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Playback speed of the rolling sound at full speed, which also changes its pitch.
+    pub fn rolling_pitch(&self) -> f32 {
+        match self {
+            Surface::Dirt => 1.0,
+            Surface::Stone => 1.4,
+            Surface::Ice => 1.8,
+            Surface::Mud => 0.6,
+            Surface::BouncePad => 1.2,
+        }
+    }
+}
+
+/// The surface the player is currently rolling on, `None` while airborne.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct PlayerSurface(pub Option<Surface>);
+
+fn detect_player_surface(
+    rapier_context: Res<RapierContext>,
+    player: Query<Entity, With<Player>>,
+    surfaces: Query<&Surface>,
+    mut player_surface: ResMut<PlayerSurface>,
+) {
+    let Ok(player) = player.get_single() else {
+        player_surface.0 = None;
+        return;
+    };
+
+    // the surface whose contact normal points up the most is the one we are standing on
+    let mut ground: Option<(f32, Surface)> = None;
+    for contact_pair in rapier_context.contact_pairs_with(player) {
+        if !contact_pair.has_any_active_contact() {
+            continue;
+        }
+        let (other, normal_sign) = if contact_pair.collider1() == player {
+            (contact_pair.collider2(), -1.)
+        } else {
+            (contact_pair.collider1(), 1.)
+        };
+        let Ok(surface) = surfaces.get(other) else {
+            continue;
+        };
+        // manifolds without solver contacts are ignored, e.g. when passing through a one-way platform
+        for manifold in contact_pair
+            .manifolds()
+            .filter(|manifold| manifold.num_solver_contacts() > 0)
+        {
+            // normal pointing from the surface to the player
+            let up = normal_sign * manifold.normal().y;
+            if ground.is_none_or(|(best, _)| up > best) {
+                ground = Some((up, *surface));
+            }
+        }
+    }
+
+    player_surface.0 = ground
+        .filter(|(up, _)| *up > 0.)
+        .map(|(_, surface)| surface);
+}
+
+fn apply_surface_damping(
+    player_surface: Res<PlayerSurface>,
+    mut player: Query<&mut Damping, With<Player>>,
+) {
+    let extra = player_surface.0.map_or(0., |surface| surface.damping());
+    for mut damping in &mut player {
+        damping.linear_damping = (PLAYER_LINEAR_DAMPING + extra).max(0.);
+    }
+}
+
+/// The looping sound of the wheel rolling over the ground, a child of the player.
+#[derive(Component)]
+pub struct RollingSound {
+    volume: f32,
+}
+
+fn spawn_rolling_sound(
+    mut commands: Commands,
+    sfx_handles: Res<HandleMap<SfxKey>>,
+    player: Query<Entity, Added<Player>>,
+) {
+    for player in &player {
+        commands.entity(player).with_children(|children| {
+            children.spawn((
+                Name::new("Rolling Sound"),
+                AudioSourceBundle {
+                    source: sfx_handles[&SfxKey::Rolling].clone_weak(),
+                    settings: PlaybackSettings {
+                        mode: PlaybackMode::Loop,
+                        volume: Volume::new(0.),
+                        ..default()
+                    },
+                },
+                RollingSound { volume: 0. },
+                PauseWithGame,
+            ));
+        });
+    }
+}
+
+// pitch and volume follow the speed, silent while airborne
+fn update_rolling_sound(
+    time: Res<Time>,
+    audio_settings: Res<AudioSettings>,
+    player_surface: Res<PlayerSurface>,
+    player: Query<&Velocity, With<Player>>,
+    mut rolling_sound: Query<(&mut RollingSound, &AudioSink)>,
+) {
+    let Ok(velocity) = player.get_single() else {
+        return;
+    };
+    let speed = (velocity.linvel.length() / ROLLING_SOUND_FULL_SPEED).min(1.);
+
+    for (mut sound, sink) in &mut rolling_sound {
+        let target = match player_surface.0 {
+            Some(surface) => {
+                sink.set_speed(surface.rolling_pitch() * (0.5 + 0.5 * speed));
+                speed
+            }
+            None => 0.,
+        };
+        // smoothed, so touching the ground for a single frame doesn't crackle
+        let response = (ROLLING_SOUND_RESPONSE * time.delta_seconds()).min(1.);
+        sound.volume += (target - sound.volume) * response;
+        sink.set_volume(audio_settings.sfx_volume() * sound.volume);
+    }
+}
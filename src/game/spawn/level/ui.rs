@@ -4,19 +4,32 @@ use bevy::prelude::*;
 
 use crate::game::assets::{HandleMap, ImageKey};
 use crate::screen::Screen;
+use crate::settings::AccessibilitySettings;
 use crate::ui::prelude::*;
 
 use super::items::{BluberryTimer, ItemType, Items};
+use super::palette::StatePalette;
 use super::{CurrentLevel, LevelTimer};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(enter_level).add_systems(
         Update,
-        (update_hampter_ui, update_bluberry_ui, update_level_timer_ui)
+        (
+            rebuild_hud.run_if(resource_changed::<AccessibilitySettings>),
+            update_hampter_ui,
+            color_missing_hampters,
+            update_bluberry_ui,
+            update_level_timer_ui,
+        )
+            .chain()
             .run_if(in_state(Screen::Playing)),
     );
 }
 
+/// The root nodes of the HUD, respawned when the HUD scale changes.
+#[derive(Component, Default)]
+struct Hud;
+
 // u8 used to store which hampter has been collected
 #[derive(Component, Default, PartialEq, PartialOrd, Ord, Eq)]
 struct HampterIconMarker(pub u8);
@@ -41,15 +54,18 @@ pub fn enter_level(
     mut commands: Commands,
     handles: Res<HandleMap<ImageKey>>,
     current_level: Res<CurrentLevel>,
+    settings: Res<AccessibilitySettings>,
 ) {
+    let scale = settings.hud_scale;
+
     // hampter ui spawn
     commands
         .spawn(NodeBundle {
             background_color: BackgroundColor(Color::NONE),
             // border_radius: BorderRadius::all(Val::Px(25.)),
             style: Style {
-                width: Val::Percent(4.5 * current_level.hampters() as f32),
-                height: Val::Percent(7.5),
+                width: Val::Percent(4.5 * current_level.hampters() as f32 * scale),
+                height: Val::Percent(7.5 * scale),
                 justify_self: JustifySelf::End,
                 justify_content: JustifyContent::SpaceBetween,
                 align_content: AlignContent::SpaceEvenly,
@@ -57,7 +73,7 @@ pub fn enter_level(
             },
             ..default()
         })
-        .insert(StateScoped(Screen::Playing))
+        .insert((Hud, StateScoped(Screen::Playing)))
         .with_children(|child| {
             for (item, count) in current_level.items() {
                 if matches!(item, ItemType::Hampter) {
@@ -77,8 +93,8 @@ pub fn enter_level(
             // border_radius: BorderRadius::all(Val::Px(25.)),
             style: Style {
                 width: Val::Percent(0.),
-                height: Val::Percent(7.5),
-                top: Val::Percent(10.),
+                height: Val::Percent(7.5 * scale),
+                top: Val::Percent(10. * scale),
                 justify_self: JustifySelf::End,
                 justify_content: JustifyContent::SpaceBetween,
                 align_content: AlignContent::SpaceEvenly,
@@ -87,7 +103,7 @@ pub fn enter_level(
             ..default()
         })
         .insert(BluberryNodeMarker)
-        .insert(StateScoped(Screen::Playing));
+        .insert((Hud, StateScoped(Screen::Playing)));
 
     // bluberry timer
     commands
@@ -95,9 +111,9 @@ pub fn enter_level(
             background_color: BackgroundColor(Color::NONE),
             // border_radius: BorderRadius::all(Val::Px(25.)),
             style: Style {
-                width: Val::Percent(5.),
-                height: Val::Percent(5.),
-                top: Val::Percent(17.),
+                width: Val::Percent(5. * scale),
+                height: Val::Percent(5. * scale),
+                top: Val::Percent(17. * scale),
                 justify_self: JustifySelf::End,
                 justify_content: JustifyContent::Center,
                 align_content: AlignContent::Center,
@@ -105,15 +121,11 @@ pub fn enter_level(
             },
             ..default()
         })
-        .insert(StateScoped(Screen::Playing))
+        .insert((Hud, StateScoped(Screen::Playing)))
         .with_children(|c| {
-            c.spawn(TextBundle::from_section(
-                "",
-                TextStyle {
-                    font_size: 24.0,
-                    color: Color::BLACK,
-                    ..default()
-                },
+            c.spawn((
+                TextBundle::from_section("", TextStyle::default()),
+                ThemedText::Hud,
             ))
             .insert(BluberryTimerMarker);
         });
@@ -125,7 +137,7 @@ pub fn enter_level(
             // border_radius: BorderRadius::all(Val::Px(25.)),
             style: Style {
                 width: Val::Auto,
-                height: Val::Percent(5.),
+                height: Val::Percent(5. * scale),
                 justify_self: JustifySelf::End,
                 align_self: AlignSelf::End,
                 justify_content: JustifyContent::Center,
@@ -134,15 +146,11 @@ pub fn enter_level(
             },
             ..default()
         })
-        .insert(StateScoped(Screen::Playing))
+        .insert((Hud, StateScoped(Screen::Playing)))
         .with_children(|c| {
-            c.spawn(TextBundle::from_section(
-                "",
-                TextStyle {
-                    font_size: 24.0,
-                    color: Color::BLACK,
-                    ..default()
-                },
+            c.spawn((
+                TextBundle::from_section("", TextStyle::default()),
+                ThemedText::Hud,
             ))
             .insert(LevelTimerMarker);
         });
@@ -172,6 +180,26 @@ fn update_hampter_ui(
     }
 }
 
+// with the colorblind palette missing hampters are darkened,
+// so they don't only differ from collected ones by color
+fn color_missing_hampters(
+    settings: Res<AccessibilitySettings>,
+    mut icons: Query<(&HampterIconMarker, &mut UiImage)>,
+    items: Query<&Items>,
+) {
+    let palette = StatePalette::new(&settings);
+    let hampter_count = items
+        .iter()
+        .map(|items| *items.0.get(&ItemType::Hampter).unwrap_or(&0))
+        .max()
+        .unwrap_or(0);
+    for (marker, mut image) in &mut icons {
+        if marker.0 >= hampter_count && image.color != palette.missing_hampter {
+            image.color = palette.missing_hampter;
+        }
+    }
+}
+
 fn update_bluberry_ui(
     mut bluberry_ui_node: Query<(Entity, &mut Style), With<BluberryNodeMarker>>,
     mut bluberry_timer_text: Query<&mut Text, With<BluberryTimerMarker>>,
@@ -180,6 +208,7 @@ fn update_bluberry_ui(
     handles: Res<HandleMap<ImageKey>>,
     mut commands: Commands,
     items: Query<&Items>,
+    settings: Res<AccessibilitySettings>,
 ) {
     if let Ok((e, mut s)) = bluberry_ui_node.get_single_mut() {
         // Collect bluberry icons first
@@ -193,7 +222,7 @@ fn update_bluberry_ui(
                 let bluberry_count = item.0.get(&ItemType::Bluberry).unwrap_or(&0);
 
                 // adjust node width
-                s.width = Val::Percent(4.5 * *bluberry_count as f32);
+                s.width = Val::Percent(4.5 * *bluberry_count as f32 * settings.hud_scale);
                 let length = bluberry_is.len() as u8;
 
                 match length.cmp(bluberry_count) {
@@ -210,22 +239,16 @@ fn update_bluberry_ui(
                         }
                     }
                     Ordering::Equal => {
-                        *bluberry_timer_text.single_mut() = Text::from_section(
-                            if *bluberry_count == 0 {
-                                "".into()
-                            } else {
-                                format!(
-                                    "{:.2}",
-                                    (bluberry_timer.0.duration() * *bluberry_count as u32
-                                        - bluberry_timer.0.elapsed())
-                                    .as_secs_f32()
-                                )
-                            },
-                            TextStyle {
-                                color: Color::BLACK,
-                                ..default()
-                            },
-                        )
+                        // only the text, the style comes from the theme
+                        bluberry_timer_text.single_mut().sections[0].value = if *bluberry_count == 0
+                        {
+                            "".into()
+                        } else {
+                            format!(
+                                "{:.2}",
+                                bluberry_timer.remaining(*bluberry_count).as_secs_f32()
+                            )
+                        };
                     }
                 }
             }
@@ -243,12 +266,21 @@ fn update_level_timer_ui(
     level_timer: Res<LevelTimer>,
 ) {
     if let Ok(mut timer_text) = timer_text.get_single_mut() {
-        *timer_text = Text::from_section(
-            format!("{:.2}", level_timer.0.elapsed_secs()),
-            TextStyle {
-                color: Color::BLACK,
-                ..default()
-            },
-        );
+        timer_text.sections[0].value = format!("{:.2}", level_timer.0.elapsed_secs());
+    }
+}
+
+// the icons and texts are brought up to date again by the systems above
+fn rebuild_hud(
+    mut commands: Commands,
+    settings: Res<AccessibilitySettings>,
+    hud: Query<Entity, With<Hud>>,
+) {
+    if settings.is_added() || hud.is_empty() {
+        return;
+    }
+    for entity in &hud {
+        commands.entity(entity).despawn_recursive();
     }
+    commands.trigger(StartLevelUi);
 }
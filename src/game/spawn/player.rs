@@ -7,12 +7,13 @@ use crate::{
     game::{
         animation::AsepriteAnimationBundleWrapper,
         assets::{AsepriteKey, HandleMap},
+        audio::sfx::{PlaySfx, Sfx},
         movement::{MovementConfig, MovementController},
     },
     screen::Screen,
 };
 
-use super::level::{components::ColliderBundle, items::Items};
+use super::level::{components::ColliderBundle, items::Items, respawn::Dying};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -21,11 +22,23 @@ pub(super) fn plugin(app: &mut App) {
             on_player_bundle_added,
             update_hamster_orientation,
             control_hamster_animation_speed,
+            play_footsteps,
+            move_audio_listener,
         )
             .run_if(in_state(Screen::Playing)),
     );
 }
 
+/// Distance between the ears of the [`SpatialListener`] following the player.
+const EAR_GAP: f32 = 120.;
+
+/// Footsteps per second for every unit of animation speed,
+/// two for each run through the 8 frames of 100ms of the hamster animation.
+const STEPS_PER_ANIMATION_SECOND: f32 = 2.5;
+
+/// Angular velocity below which the hamster is too slow to be heard.
+const MIN_STEP_ANGVEL: f32 = 1.;
+
 #[derive(Component)]
 pub struct Hamster;
 
@@ -33,7 +46,10 @@ fn on_player_bundle_added(
     handles: Res<HandleMap<AsepriteKey>>,
     mut commands: Commands,
     player: Query<Entity, Added<Player>>,
+    listeners: Query<(), With<SpatialListener>>,
 ) {
+    // the player is spawned again with every level, but there is only one listener
+    let mut has_listener = !listeners.is_empty();
     for p in player.iter() {
         commands.entity(p).with_children(|child| {
             child
@@ -45,6 +61,29 @@ fn on_player_bundle_added(
                 })
                 .insert(Hamster);
         });
+        if has_listener {
+            continue;
+        }
+        has_listener = true;
+        // a separate entity, so the ears don't spin with the wheel
+        commands.spawn((
+            Name::new("Audio Listener"),
+            SpatialListener::new(EAR_GAP),
+            TransformBundle::default(),
+            StateScoped(Screen::Playing),
+        ));
+    }
+}
+
+fn move_audio_listener(
+    player: Query<&GlobalTransform, With<Player>>,
+    mut listener: Query<&mut Transform, With<SpatialListener>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    for mut transform in &mut listener {
+        transform.translation = player.translation().truncate().extend(0.);
     }
 }
 
@@ -59,6 +98,29 @@ pub fn control_hamster_animation_speed(
     };
 }
 
+// in rhythm with the animation, which runs as fast as the wheel spins
+fn play_footsteps(
+    time: Res<Time>,
+    mut commands: Commands,
+    player: Query<&Velocity, (With<Player>, Without<Dying>)>,
+    mut phase: Local<f32>,
+) {
+    let Ok(vel) = player.get_single() else {
+        return;
+    };
+    if vel.angvel.abs() < MIN_STEP_ANGVEL {
+        return;
+    }
+
+    // the same speed as in `control_hamster_animation_speed`
+    let animation_speed = vel.angvel.abs() / 2.;
+    *phase += animation_speed * STEPS_PER_ANIMATION_SECOND * time.delta_seconds();
+    if *phase >= 1. {
+        *phase = phase.fract();
+        commands.trigger(PlaySfx::new(Sfx::RandomStep));
+    }
+}
+
 // A WHOLE BUNCH OF VECTOR/MATH MAGIC
 // don't ask me how i figured this out
 fn update_hamster_orientation(
@@ -2,14 +2,10 @@
 mod dev_tools;
 mod game;
 mod screen;
+mod settings;
 mod ui;
 
-use bevy::{
-    asset::AssetMetaCheck,
-    audio::{AudioPlugin, Volume},
-    log::LogPlugin,
-    prelude::*,
-};
+use bevy::{asset::AssetMetaCheck, log::LogPlugin, prelude::*};
 
 pub struct AppPlugin;
 
@@ -50,17 +46,11 @@ impl Plugin for AppPlugin {
                     }
                     .into(),
                     ..default()
-                })
-                .set(AudioPlugin {
-                    global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
-                    },
-                    ..default()
                 }),
         );
 
         // Add other plugins.
-        app.add_plugins((game::plugin, screen::plugin, ui::plugin));
+        app.add_plugins((settings::plugin, game::plugin, screen::plugin, ui::plugin));
 
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
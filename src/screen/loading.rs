@@ -13,7 +13,11 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), enter_loading);
     app.add_systems(
         Update,
-        continue_to_title.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        (
+            update_loading_progress,
+            continue_to_title.run_if(all_assets_loaded),
+        )
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
@@ -23,16 +27,47 @@ fn enter_loading(mut commands: Commands) {
         .insert(StateScoped(Screen::Loading))
         .with_children(|children| {
             children.label("Loading...");
+            children.progress_bar(0.);
         });
 }
 
+fn update_loading_progress(
+    asset_server: Res<AssetServer>,
+    image_handles: Res<HandleMap<ImageKey>>,
+    sfx_handles: Res<HandleMap<SfxKey>>,
+    soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    ui_theme: Res<UiTheme>,
+    mut progress_bars: Query<&mut ProgressBar>,
+) {
+    // the theme counts as one asset, together with its font
+    let theme_loaded = asset_server.is_loaded_with_dependencies(&ui_theme.0);
+    let (loaded, total) = [
+        image_handles.loaded_count(&asset_server),
+        sfx_handles.loaded_count(&asset_server),
+        soundtrack_handles.loaded_count(&asset_server),
+        (usize::from(theme_loaded), 1),
+    ]
+    .into_iter()
+    .fold((0, 0), |(loaded, total), (l, t)| (loaded + l, total + t));
+    let value = loaded as f32 / total.max(1) as f32;
+
+    for mut progress_bar in &mut progress_bars {
+        if progress_bar.value != value {
+            progress_bar.value = value;
+        }
+    }
+}
+
 fn all_assets_loaded(
     asset_server: Res<AssetServer>,
     image_handles: Res<HandleMap<ImageKey>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    ui_theme: Res<UiTheme>,
 ) -> bool {
-    image_handles.all_loaded(&asset_server)
+    // the menus would show up in the default font otherwise
+    asset_server.is_loaded_with_dependencies(&ui_theme.0)
+        && image_handles.all_loaded(&asset_server)
         && sfx_handles.all_loaded(&asset_server)
         && soundtrack_handles.all_loaded(&asset_server)
 }
@@ -2,12 +2,16 @@
 
 mod credits;
 mod loading;
+mod pause;
 mod playing;
+mod settings;
 mod splash;
 mod title;
 
 use bevy::prelude::*;
 
+pub use pause::Pause;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
     app.enable_state_scoped_entities::<Screen>();
@@ -17,7 +21,9 @@ pub(super) fn plugin(app: &mut App) {
         loading::plugin,
         title::plugin,
         credits::plugin,
+        settings::plugin,
         playing::plugin,
+        pause::plugin,
     ));
 }
 
@@ -29,5 +35,6 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    Settings,
     Playing,
 }
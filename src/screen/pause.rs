@@ -0,0 +1,123 @@
+//! The pause menu, opened with Escape while playing.
+//! Time, physics and the game sounds stand still while the game isn't running.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_rapier2d::plugin::RapierConfiguration;
+
+use super::{settings::spawn_settings_menu, Screen};
+use crate::{
+    settings::{AccessibilitySettings, AudioSettings, DisplaySettings},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<Pause>();
+    app.enable_state_scoped_entities::<Pause>();
+
+    app.add_systems(OnEnter(Pause::Running), resume_time);
+    app.add_systems(OnExit(Pause::Running), pause_time);
+    // the pause state is gone when quitting to the title, without entering `Running` again
+    app.add_systems(OnExit(Screen::Playing), resume_time);
+    app.add_systems(OnEnter(Pause::Paused), enter_paused);
+    app.add_systems(OnEnter(Pause::Settings), enter_pause_settings);
+
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        (
+            toggle_pause.run_if(input_just_pressed(KeyCode::Escape).and_then(not(typing))),
+            handle_pause_action.run_if(in_state(Pause::Paused)),
+        ),
+    );
+}
+
+const PAUSE_BACKGROUND: Color = Color::srgba(0., 0., 0., 0.7);
+
+/// Whether the game is running while playing.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum Pause {
+    #[default]
+    Running,
+    Paused,
+    /// The settings menu opened from the pause menu.
+    Settings,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    Settings,
+    Quit,
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>, mut rapier_config: ResMut<RapierConfiguration>) {
+    time.pause();
+    rapier_config.physics_pipeline_active = false;
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>, mut rapier_config: ResMut<RapierConfiguration>) {
+    time.unpause();
+    rapier_config.physics_pipeline_active = true;
+}
+
+fn enter_paused(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((
+            BackgroundColor(PAUSE_BACKGROUND),
+            StateScoped(Pause::Paused),
+        ))
+        .with_children(|children| {
+            children.header("Paused");
+            children.button("Resume").insert(PauseAction::Resume);
+            children.button("Settings").insert(PauseAction::Settings);
+            children.button("Quit").insert(PauseAction::Quit);
+        });
+}
+
+fn enter_pause_settings(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    display_settings: Res<DisplaySettings>,
+    accessibility_settings: Res<AccessibilitySettings>,
+) {
+    spawn_settings_menu(
+        &mut commands,
+        &audio_settings,
+        &display_settings,
+        &accessibility_settings,
+    )
+    .insert((
+        BackgroundColor(PAUSE_BACKGROUND),
+        StateScoped(Pause::Settings),
+    ));
+}
+
+fn toggle_pause(pause: Option<Res<State<Pause>>>, mut next_pause: ResMut<NextState<Pause>>) {
+    let Some(pause) = pause else {
+        return;
+    };
+    next_pause.set(match pause.get() {
+        Pause::Running => Pause::Paused,
+        Pause::Paused => Pause::Running,
+        Pause::Settings => Pause::Paused,
+    });
+}
+
+fn handle_pause_action(
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut button_query: InteractionQuery<&PauseAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_pause.set(Pause::Running),
+                PauseAction::Settings => next_pause.set(Pause::Settings),
+                PauseAction::Quit => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! A settings screen that can be accessed from the title screen,
+//! and the same menu shown over the level from the pause menu.
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use super::{pause::Pause, Screen};
+#[cfg(not(target_family = "wasm"))]
+use crate::settings::WindowModeSetting;
+use crate::{
+    settings::{AccessibilitySettings, AudioSettings, DisplaySettings, HUD_SCALE_RANGE},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), enter_settings);
+
+    app.add_systems(
+        Update,
+        handle_settings_action
+            .run_if(in_state(Screen::Settings).or_else(in_state(Pause::Settings))),
+    );
+    app.register_type::<SettingsAction>();
+}
+
+/// The smallest change of the HUD scale made by dragging its slider.
+const HUD_SCALE_STEP: f32 = 0.05;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    Back,
+}
+
+fn enter_settings(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    display_settings: Res<DisplaySettings>,
+    accessibility_settings: Res<AccessibilitySettings>,
+) {
+    spawn_settings_menu(
+        &mut commands,
+        &audio_settings,
+        &display_settings,
+        &accessibility_settings,
+    )
+    .insert(StateScoped(Screen::Settings));
+}
+
+/// Spawns the settings menu, the caller decides when it is despawned.
+pub(super) fn spawn_settings_menu<'a>(
+    commands: &'a mut Commands,
+    audio_settings: &AudioSettings,
+    display_settings: &DisplaySettings,
+    accessibility_settings: &AccessibilitySettings,
+) -> EntityCommands<'a> {
+    let mut root = commands.ui_root();
+    root.with_children(|children| {
+        children.label("Audio");
+        children
+            .slider("Master", audio_settings.master)
+            .observe(set_master_volume);
+        children
+            .slider("Music", audio_settings.music)
+            .observe(set_music_volume);
+        children
+            .slider("Sound effects", audio_settings.sfx)
+            .observe(set_sfx_volume);
+
+        // the web page decides how the canvas is shown
+        #[cfg(not(target_family = "wasm"))]
+        {
+            children.label("Display");
+            children
+                .dropdown(
+                    "Window",
+                    WindowModeSetting::ALL
+                        .iter()
+                        .map(|mode| mode.name().to_string())
+                        .collect(),
+                    WindowModeSetting::ALL
+                        .iter()
+                        .position(|mode| *mode == display_settings.window_mode)
+                        .unwrap_or_default(),
+                )
+                .observe(set_window_mode);
+            children
+                .toggle("VSync", display_settings.vsync)
+                .observe(set_vsync);
+        }
+        #[cfg(target_family = "wasm")]
+        let _ = display_settings;
+
+        children.label("Accessibility");
+        children
+            .toggle("Screen shake", accessibility_settings.screen_shake)
+            .observe(set_screen_shake);
+        let (min, max) = HUD_SCALE_RANGE;
+        children
+            .slider(
+                "HUD size",
+                (accessibility_settings.hud_scale - min) / (max - min),
+            )
+            .observe(set_hud_scale);
+        children
+            .toggle("Colorblind colors", accessibility_settings.colorblind)
+            .observe(set_colorblind);
+
+        children.button("Back").insert(SettingsAction::Back);
+    });
+    root
+}
+
+fn set_master_volume(trigger: Trigger<SliderChanged>, mut audio: ResMut<AudioSettings>) {
+    audio.master = trigger.event().0;
+}
+
+fn set_music_volume(trigger: Trigger<SliderChanged>, mut audio: ResMut<AudioSettings>) {
+    audio.music = trigger.event().0;
+}
+
+fn set_sfx_volume(trigger: Trigger<SliderChanged>, mut audio: ResMut<AudioSettings>) {
+    audio.sfx = trigger.event().0;
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn set_window_mode(trigger: Trigger<DropdownChanged>, mut display: ResMut<DisplaySettings>) {
+    display.window_mode = WindowModeSetting::ALL[trigger.event().0];
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn set_vsync(trigger: Trigger<ToggleChanged>, mut display: ResMut<DisplaySettings>) {
+    display.vsync = trigger.event().0;
+}
+
+fn set_screen_shake(
+    trigger: Trigger<ToggleChanged>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+) {
+    accessibility.screen_shake = trigger.event().0;
+}
+
+// the HUD is rebuilt whenever its scale changes, so dragging only changes it in steps
+fn set_hud_scale(
+    trigger: Trigger<SliderChanged>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+) {
+    let (min, max) = HUD_SCALE_RANGE;
+    let scale = min + trigger.event().0 * (max - min);
+    let scale = (scale / HUD_SCALE_STEP).round() * HUD_SCALE_STEP;
+    if accessibility.hud_scale != scale {
+        accessibility.hud_scale = scale;
+    }
+}
+
+fn set_colorblind(
+    trigger: Trigger<ToggleChanged>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+) {
+    accessibility.colorblind = trigger.event().0;
+}
+
+fn handle_settings_action(
+    pause: Option<Res<State<Pause>>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut button_query: InteractionQuery<&SettingsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                // back to where the settings were opened from
+                SettingsAction::Back if pause.is_some() => next_pause.set(Pause::Paused),
+                SettingsAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
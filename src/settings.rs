@@ -0,0 +1,279 @@
+//! Player settings, saved on native builds as RON to [`SETTINGS_FILE`]
+//! in the folder of the game's executable, wherever the game is launched from.
+//!
+//! Missing or unknown settings are ignored and out of range values are clamped,
+//! a file that doesn't parse at all is replaced by the defaults.
+
+use bevy::prelude::*;
+#[cfg(not(target_family = "wasm"))]
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    let file = read_settings_file().clamped();
+
+    app.register_type::<AudioSettings>();
+    app.insert_resource(file.audio);
+    app.register_type::<DisplaySettings>();
+    app.insert_resource(file.display);
+    app.register_type::<AccessibilitySettings>();
+    app.insert_resource(file.accessibility);
+
+    app.add_systems(
+        Update,
+        save_settings.run_if(
+            resource_changed::<AudioSettings>
+                .or_else(resource_changed::<DisplaySettings>)
+                .or_else(resource_changed::<AccessibilitySettings>),
+        ),
+    );
+    // the web page decides how the canvas is shown
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(
+        Update,
+        apply_display_settings.run_if(resource_changed::<DisplaySettings>),
+    );
+}
+
+#[cfg(not(target_family = "wasm"))]
+const SETTINGS_FILE: &str = "settings.ron";
+
+/// All settings, as they are written to the file.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+struct SettingsFile {
+    audio: AudioSettings,
+    display: DisplaySettings,
+    accessibility: AccessibilitySettings,
+}
+
+impl SettingsFile {
+    /// Brings values edited by hand back into range.
+    fn clamped(mut self) -> Self {
+        let audio = &mut self.audio;
+        for volume in [&mut audio.master, &mut audio.music, &mut audio.sfx] {
+            *volume = volume.clamp(0., 1.);
+        }
+        let accessibility = &mut self.accessibility;
+        accessibility.hud_scale = accessibility
+            .hud_scale
+            .clamp(HUD_SCALE_RANGE.0, HUD_SCALE_RANGE.1);
+        self
+    }
+}
+
+/// Volumes from 0 to 1.
+/// The volume of a sound is the master volume times the volume of its channel.
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            // as loud as the fixed global volume used before
+            master: 0.3,
+            music: 1.0,
+            sfx: 1.0,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+/// How the window is shown, only used on native builds.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub const ALL: [WindowModeSetting; 3] = [
+        WindowModeSetting::Windowed,
+        WindowModeSetting::Borderless,
+        WindowModeSetting::Fullscreen,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "Windowed",
+            WindowModeSetting::Borderless => "Borderless",
+            WindowModeSetting::Fullscreen => "Fullscreen",
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn window_mode(&self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => WindowMode::BorderlessFullscreen,
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowModeSetting::Windowed,
+            vsync: true,
+        }
+    }
+}
+
+/// The smallest and biggest [`AccessibilitySettings::hud_scale`].
+pub const HUD_SCALE_RANGE: (f32, f32) = (0.5, 1.5);
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub screen_shake: bool,
+    /// Size of the in-game HUD, 1 is the normal size.
+    pub hud_scale: f32,
+    /// Use colors that can be told apart with color vision deficiencies.
+    pub colorblind: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            hud_scale: 1.0,
+            colorblind: false,
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn settings_path() -> Option<std::path::PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(SETTINGS_FILE))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_settings_file() -> SettingsFile {
+    let Some(contents) = settings_path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return SettingsFile::default();
+    };
+    ron::from_str(&contents).unwrap_or_else(|err| {
+        warn!("Couldn't parse {SETTINGS_FILE}, using the default settings: {err}");
+        SettingsFile::default()
+    })
+}
+
+// there is no file system on the web, so the defaults are used every time
+#[cfg(target_family = "wasm")]
+fn read_settings_file() -> SettingsFile {
+    SettingsFile::default()
+}
+
+fn save_settings(
+    audio: Res<AudioSettings>,
+    display: Res<DisplaySettings>,
+    accessibility: Res<AccessibilitySettings>,
+) {
+    // the resources count as changed when they are inserted, there is nothing new to save then
+    if audio.is_added() || display.is_added() || accessibility.is_added() {
+        return;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let Some(path) = settings_path() else {
+            warn!("Couldn't find the folder of the game to save the settings in");
+            return;
+        };
+        let file = SettingsFile {
+            audio: *audio,
+            display: *display,
+            accessibility: *accessibility,
+        };
+        let result = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|contents| std::fs::write(&path, contents).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Couldn't save settings to {}: {err}", path.display());
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn apply_display_settings(
+    display: Res<DisplaySettings>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    window.mode = display.window_mode.window_mode();
+    window.present_mode = if display.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let file = SettingsFile {
+            audio: AudioSettings {
+                master: 0.5,
+                ..default()
+            },
+            display: DisplaySettings {
+                window_mode: WindowModeSetting::Borderless,
+                vsync: false,
+            },
+            accessibility: AccessibilitySettings {
+                colorblind: true,
+                ..default()
+            },
+        };
+        let contents = ron::to_string(&file).unwrap();
+        let read: SettingsFile = ron::from_str(&contents).unwrap();
+        assert_eq!(read.audio, file.audio);
+        assert_eq!(read.display, file.display);
+        assert_eq!(read.accessibility, file.accessibility);
+    }
+
+    #[test]
+    fn missing_settings_are_defaults_and_values_are_clamped() {
+        let read: SettingsFile =
+            ron::from_str("(audio: (music: 3.0), accessibility: (hud_scale: 0.1), old: 1)")
+                .unwrap();
+        let read = read.clamped();
+        assert_eq!(read.audio.music, 1.0);
+        assert_eq!(read.audio.master, AudioSettings::default().master);
+        assert_eq!(read.display, DisplaySettings::default());
+        assert_eq!(read.accessibility.hud_scale, HUD_SCALE_RANGE.0);
+    }
+}
@@ -0,0 +1,388 @@
+//! State and behavior of interactive widgets.
+//!
+//! Widgets trigger a changed event targeting themselves when the player changes their value,
+//! observe it on the widget to bind it to a resource:
+//!
+//! ```ignore
+//! children
+//!     .slider("Music", settings.music)
+//!     .observe(|trigger: Trigger<SliderChanged>, mut settings: ResMut<AudioSettings>| {
+//!         settings.music = trigger.event().0;
+//!     });
+//! ```
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Slider>();
+    app.register_type::<Toggle>();
+    app.register_type::<Dropdown>();
+    app.register_type::<ProgressBar>();
+    app.register_type::<TextInput>();
+    app.init_resource::<TypingFocus>();
+    app.add_systems(
+        Update,
+        (
+            (drag_sliders, update_slider_fill).chain(),
+            (flip_toggles, update_toggle_text).chain(),
+            (open_dropdowns, select_dropdown_options, update_dropdowns).chain(),
+            update_progress_bar_fill,
+            (
+                focus_text_inputs,
+                type_into_text_inputs,
+                update_text_input_text,
+            )
+                .chain(),
+        ),
+    );
+    app.add_systems(PostUpdate, update_typing_focus);
+}
+
+/// Triggered on a [`Slider`] when it is dragged to a new value.
+#[derive(Event, Debug)]
+pub struct SliderChanged(pub f32);
+
+/// Triggered on a [`Toggle`] when it is flipped.
+#[derive(Event, Debug)]
+pub struct ToggleChanged(pub bool);
+
+/// Triggered on a [`Dropdown`] with the index of the newly selected option.
+#[derive(Event, Debug)]
+pub struct DropdownChanged(pub usize);
+
+/// Triggered on a [`TextInput`] whenever its text is edited.
+#[derive(Event, Debug)]
+pub struct TextInputChanged(pub String);
+
+/// Triggered on a [`TextInput`] when Enter is pressed in it.
+#[derive(Event, Debug)]
+pub struct TextInputSubmitted(pub String);
+
+/// A slider with a value from 0 to 1, set by clicking or dragging its track.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Slider {
+    pub value: f32,
+    /// The node showing the value, as wide as the value's share of the track.
+    pub fill: Entity,
+}
+
+/// The clickable part of a [`Slider`], a child of it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SliderTrack;
+
+fn drag_sliders(
+    mut commands: Commands,
+    window: Query<&Window, With<PrimaryWindow>>,
+    tracks: Query<(&Interaction, &Node, &GlobalTransform, &Parent), With<SliderTrack>>,
+    mut sliders: Query<&mut Slider>,
+) {
+    let Some(cursor) = window.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    for (interaction, node, transform, parent) in &tracks {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let Ok(mut slider) = sliders.get_mut(parent.get()) else {
+            continue;
+        };
+
+        // ui nodes are positioned by their center
+        let left = transform.translation().x - node.size().x / 2.;
+        let value = ((cursor.x - left) / node.size().x).clamp(0., 1.);
+        if slider.value != value {
+            slider.value = value;
+            commands.trigger_targets(SliderChanged(value), parent.get());
+        }
+    }
+}
+
+fn update_slider_fill(sliders: Query<&Slider, Changed<Slider>>, mut styles: Query<&mut Style>) {
+    for slider in &sliders {
+        if let Ok(mut style) = styles.get_mut(slider.fill) {
+            style.width = Val::Percent(slider.value * 100.);
+        }
+    }
+}
+
+/// An on/off switch, flipped by clicking its button.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Toggle {
+    pub value: bool,
+    /// The text showing the value.
+    pub text: Entity,
+}
+
+/// The clickable part of a [`Toggle`], a child of it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct ToggleButton;
+
+fn flip_toggles(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &Parent), (Changed<Interaction>, With<ToggleButton>)>,
+    mut toggles: Query<&mut Toggle>,
+) {
+    for (interaction, parent) in &buttons {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(mut toggle) = toggles.get_mut(parent.get()) {
+            toggle.value = !toggle.value;
+            commands.trigger_targets(ToggleChanged(toggle.value), parent.get());
+        }
+    }
+}
+
+fn update_toggle_text(toggles: Query<&Toggle, Changed<Toggle>>, mut texts: Query<&mut Text>) {
+    for toggle in &toggles {
+        if let Ok(mut text) = texts.get_mut(toggle.text) {
+            text.sections[0].value = toggle_text(toggle.value).to_string();
+        }
+    }
+}
+
+pub fn toggle_text(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+/// A choice between several options, shown in a list below its button while open.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Dropdown {
+    pub options: Vec<String>,
+    pub selected: usize,
+    pub open: bool,
+    /// The text showing the selected option.
+    pub text: Entity,
+    /// The node containing the [`DropdownOption`]s.
+    pub list: Entity,
+}
+
+/// The button opening and closing a [`Dropdown`], a child of it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DropdownButton;
+
+/// A button selecting the option with this index of the [`Dropdown`] it belongs to.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DropdownOption {
+    pub dropdown: Entity,
+    pub index: usize,
+}
+
+fn open_dropdowns(
+    buttons: Query<(&Interaction, &Parent), (Changed<Interaction>, With<DropdownButton>)>,
+    mut dropdowns: Query<&mut Dropdown>,
+) {
+    for (interaction, parent) in &buttons {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(mut dropdown) = dropdowns.get_mut(parent.get()) {
+            dropdown.open = !dropdown.open;
+        }
+    }
+}
+
+fn select_dropdown_options(
+    mut commands: Commands,
+    options: Query<(&Interaction, &DropdownOption), Changed<Interaction>>,
+    mut dropdowns: Query<&mut Dropdown>,
+) {
+    for (interaction, option) in &options {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(mut dropdown) = dropdowns.get_mut(option.dropdown) {
+            if dropdown.selected != option.index {
+                dropdown.selected = option.index;
+                commands.trigger_targets(DropdownChanged(option.index), option.dropdown);
+            }
+            dropdown.open = false;
+        }
+    }
+}
+
+fn update_dropdowns(
+    dropdowns: Query<&Dropdown, Changed<Dropdown>>,
+    mut texts: Query<&mut Text>,
+    mut styles: Query<&mut Style>,
+) {
+    for dropdown in &dropdowns {
+        if let (Ok(mut text), Some(option)) = (
+            texts.get_mut(dropdown.text),
+            dropdown.options.get(dropdown.selected),
+        ) {
+            text.sections[0].value.clone_from(option);
+        }
+        if let Ok(mut style) = styles.get_mut(dropdown.list) {
+            style.display = if dropdown.open {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+}
+
+/// A bar filled to its value from 0 to 1, for showing progress.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct ProgressBar {
+    pub value: f32,
+    /// The node showing the value, as wide as the value's share of the bar.
+    pub fill: Entity,
+}
+
+fn update_progress_bar_fill(
+    bars: Query<&ProgressBar, Changed<ProgressBar>>,
+    mut styles: Query<&mut Style>,
+) {
+    for bar in &bars {
+        if let Ok(mut style) = styles.get_mut(bar.fill) {
+            style.width = Val::Percent(bar.value.clamp(0., 1.) * 100.);
+        }
+    }
+}
+
+/// A single line of text typed in by the player after clicking the field.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct TextInput {
+    pub value: String,
+    pub max_len: usize,
+    /// Whether typing goes into this field.
+    pub focused: bool,
+    /// The text showing the value.
+    pub text: Entity,
+}
+
+/// Whether a [`TextInput`] was focused at the end of the last frame.
+///
+/// Checked by keyboard shortcuts and menu navigation through [`typing`],
+/// so the keys typed into a field aren't used for them as well.
+/// It lags a frame behind, so the Escape or Enter that unfocuses a field is still ignored
+/// whether the shortcut runs before or after the field handles it.
+#[derive(Resource, Default, Debug)]
+pub struct TypingFocus(bool);
+
+/// Run condition that is true while the player types into a [`TextInput`].
+pub fn typing(focus: Res<TypingFocus>) -> bool {
+    focus.0
+}
+
+fn update_typing_focus(inputs: Query<&TextInput>, mut focus: ResMut<TypingFocus>) {
+    let typing = inputs.iter().any(|input| input.focused);
+    if focus.0 != typing {
+        focus.0 = typing;
+    }
+}
+
+/// The clickable field of a [`TextInput`], a child of it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct TextInputField;
+
+// clicking a field focuses it, clicking anywhere else takes the focus away
+fn focus_text_inputs(
+    mouse: Res<ButtonInput<MouseButton>>,
+    fields: Query<(&Interaction, &Parent), With<TextInputField>>,
+    mut inputs: Query<(Entity, &mut TextInput)>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let clicked = fields
+        .iter()
+        .find(|(interaction, _)| matches!(interaction, Interaction::Pressed))
+        .map(|(_, parent)| parent.get());
+    for (entity, mut input) in &mut inputs {
+        let focused = Some(entity) == clicked;
+        if input.focused != focused {
+            input.focused = focused;
+        }
+    }
+}
+
+fn type_into_text_inputs(
+    mut commands: Commands,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut inputs: Query<(Entity, &mut TextInput)>,
+) {
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        for (entity, mut input) in &mut inputs {
+            if !input.focused {
+                continue;
+            }
+            match &event.logical_key {
+                Key::Character(text) => {
+                    let room = input.max_len.saturating_sub(input.value.chars().count());
+                    let typed: String = text
+                        .chars()
+                        .filter(|c| !c.is_control())
+                        .take(room)
+                        .collect();
+                    if typed.is_empty() {
+                        continue;
+                    }
+                    input.value.push_str(&typed);
+                }
+                Key::Space if input.value.chars().count() < input.max_len => {
+                    input.value.push(' ');
+                }
+                Key::Backspace => {
+                    if input.value.pop().is_none() {
+                        continue;
+                    }
+                }
+                Key::Enter => {
+                    input.focused = false;
+                    commands.trigger_targets(TextInputSubmitted(input.value.clone()), entity);
+                    continue;
+                }
+                Key::Escape => {
+                    input.focused = false;
+                    continue;
+                }
+                _ => continue,
+            }
+            commands.trigger_targets(TextInputChanged(input.value.clone()), entity);
+        }
+    }
+}
+
+fn update_text_input_text(
+    inputs: Query<&TextInput, Changed<TextInput>>,
+    mut texts: Query<&mut Text>,
+) {
+    for input in &inputs {
+        if let Ok(mut text) = texts.get_mut(input.text) {
+            // a caret shows where typing goes
+            text.sections[0].value = if input.focused {
+                format!("{}|", input.value)
+            } else {
+                input.value.clone()
+            };
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
-use crate::game::{assets::SfxKey, audio::sfx::PlaySfx};
+use super::navigation::Focus;
+use crate::game::{
+    assets::SfxKey,
+    audio::sfx::{PlaySfx, Sfx},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<InteractionPalette>();
@@ -11,7 +15,9 @@ pub type InteractionQuery<'w, 's, T> =
     Query<'w, 's, (&'static Interaction, T), Changed<Interaction>>;
 
 /// Palette for widget interactions.
-#[derive(Component, Debug, Reflect)]
+/// A button focused by keyboard or gamepad navigation uses the `hovered` color.
+/// The colors are set by the [`Theme`](super::theme::Theme).
+#[derive(Component, Debug, Default, Reflect)]
 #[reflect(Component)]
 pub struct InteractionPalette {
     pub none: Color,
@@ -19,16 +25,32 @@ pub struct InteractionPalette {
     pub pressed: Color,
 }
 
+impl InteractionPalette {
+    pub fn color(&self, interaction: Interaction, focused: bool) -> Color {
+        match interaction {
+            Interaction::None if focused => self.hovered,
+            Interaction::None => self.none,
+            Interaction::Hovered => self.hovered,
+            Interaction::Pressed => self.pressed,
+        }
+    }
+}
+
 fn apply_interaction_palette(
-    mut palette_query: InteractionQuery<(&InteractionPalette, &mut BackgroundColor)>,
+    focus: Res<Focus>,
+    mut palette_query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &InteractionPalette,
+        &mut BackgroundColor,
+    )>,
 ) {
-    for (interaction, (palette, mut background)) in &mut palette_query {
-        *background = match interaction {
-            Interaction::None => palette.none,
-            Interaction::Hovered => palette.hovered,
-            Interaction::Pressed => palette.pressed,
+    for (entity, interaction, palette, mut background) in &mut palette_query {
+        // every button can lose or gain the focus when it changes
+        if !interaction.is_changed() && !focus.is_changed() {
+            continue;
         }
-        .into();
+        *background = palette.color(*interaction, focus.0 == Some(entity)).into();
     }
 }
 
@@ -38,8 +60,12 @@ fn trigger_interaction_sfx(
 ) {
     for interaction in &mut interactions {
         match interaction {
-            Interaction::Hovered => commands.trigger(PlaySfx::Key(SfxKey::ButtonHover)),
-            Interaction::Pressed => commands.trigger(PlaySfx::Key(SfxKey::ButtonPress)),
+            Interaction::Hovered => commands.trigger(PlaySfx::new(Sfx::Varied {
+                key: SfxKey::ButtonHover,
+                pitch: 0.05,
+                volume: 0.1,
+            })),
+            Interaction::Pressed => commands.trigger(PlaySfx::new(Sfx::Key(SfxKey::ButtonPress))),
             _ => (),
         }
     }
@@ -3,14 +3,20 @@
 // Unused utilities and re-exports may trigger these lints undesirably.
 #![allow(dead_code, unused_imports)]
 
+pub mod controls;
 pub mod interaction;
-pub mod palette;
+pub mod navigation;
+pub mod theme;
 mod widgets;
 
 pub mod prelude {
     pub use super::{
+        controls::{
+            typing, Dropdown, DropdownChanged, ProgressBar, Slider, SliderChanged, TextInput,
+            TextInputChanged, TextInputSubmitted, Toggle, ToggleChanged,
+        },
         interaction::{InteractionPalette, InteractionQuery},
-        palette as ui_palette,
+        theme::{ThemedNode, ThemedText, UiTheme},
         widgets::{Containers as _, Widgets as _},
     };
 }
@@ -18,5 +24,10 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((
+        controls::plugin,
+        interaction::plugin,
+        navigation::plugin,
+        theme::plugin,
+    ));
 }
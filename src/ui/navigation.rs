@@ -0,0 +1,194 @@
+//! Moving the focus between buttons with the arrow keys or a gamepad's d-pad,
+//! and pressing the focused button with Enter or the gamepad's south button.
+//!
+//! The focused button looks hovered, see [`InteractionPalette`](super::interaction::InteractionPalette).
+//! Pressing it sets its [`Interaction`] to pressed for one frame,
+//! so menus handle it like a mouse click.
+
+use bevy::{ecs::system::SystemParam, prelude::*, ui::UiSystem};
+
+use super::controls::{typing, Slider, SliderChanged, SliderTrack};
+use crate::game::{
+    assets::SfxKey,
+    audio::sfx::{PlaySfx, Sfx},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Focus>();
+    app.init_resource::<Focus>();
+    app.init_resource::<PressedByNavigation>();
+    // right after the mouse interactions are updated, so everything in `Update` sees the press
+    app.add_systems(
+        PreUpdate,
+        (release_pressed, focus_hovered, navigate.run_if(not(typing)))
+            .chain()
+            .after(UiSystem::Focus),
+    );
+}
+
+/// How much a focused slider moves with one press of left or right.
+const SLIDER_STEP: f32 = 0.1;
+
+/// The button that is pressed by Enter, if any.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct Focus(pub Option<Entity>);
+
+/// The button pressed by [`navigate`] last frame.
+#[derive(Resource, Default)]
+struct PressedByNavigation(Option<Entity>);
+
+/// Navigation input from the keyboard and all gamepads.
+#[derive(SystemParam)]
+struct NavigationInput<'w> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+}
+
+impl NavigationInput<'_> {
+    fn just_pressed(&self, key: KeyCode, button: GamepadButtonType) -> bool {
+        self.keyboard.just_pressed(key)
+            || self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, button))
+            })
+    }
+
+    /// The direction to move the focus in this frame.
+    fn direction(&self) -> Option<Vec2> {
+        // ui coordinates grow downwards
+        [
+            (KeyCode::ArrowUp, GamepadButtonType::DPadUp, Vec2::NEG_Y),
+            (KeyCode::ArrowDown, GamepadButtonType::DPadDown, Vec2::Y),
+            (KeyCode::ArrowLeft, GamepadButtonType::DPadLeft, Vec2::NEG_X),
+            (KeyCode::ArrowRight, GamepadButtonType::DPadRight, Vec2::X),
+        ]
+        .into_iter()
+        .find(|(key, button, _)| self.just_pressed(*key, *button))
+        .map(|(_, _, direction)| direction)
+    }
+
+    /// Whether to press the focused button this frame.
+    fn press(&self) -> bool {
+        self.just_pressed(KeyCode::Enter, GamepadButtonType::South)
+    }
+}
+
+// the mouse doesn't release buttons it didn't press
+fn release_pressed(
+    mut pressed: ResMut<PressedByNavigation>,
+    mut interactions: Query<&mut Interaction>,
+) {
+    if let Some(entity) = pressed.0.take() {
+        if let Ok(mut interaction) = interactions.get_mut(entity) {
+            interaction.set_if_neq(Interaction::None);
+        }
+    }
+}
+
+// navigating continues from the last button under the mouse
+fn focus_hovered(
+    mut focus: ResMut<Focus>,
+    buttons: Query<(Entity, &Interaction), (Changed<Interaction>, With<Button>)>,
+) {
+    for (entity, interaction) in &buttons {
+        if matches!(interaction, Interaction::Hovered) && focus.0 != Some(entity) {
+            focus.0 = Some(entity);
+        }
+    }
+}
+
+fn navigate(
+    mut commands: Commands,
+    input: NavigationInput,
+    mut focus: ResMut<Focus>,
+    mut pressed: ResMut<PressedByNavigation>,
+    mut buttons: Query<
+        (
+            Entity,
+            &Node,
+            &GlobalTransform,
+            &InheritedVisibility,
+            &mut Interaction,
+        ),
+        With<Button>,
+    >,
+    slider_tracks: Query<&Parent, With<SliderTrack>>,
+    mut sliders: Query<&mut Slider>,
+) {
+    let direction = input.direction();
+    let press = input.press();
+    if direction.is_none() && !press {
+        return;
+    }
+
+    // hidden buttons, like the options of a closed dropdown, can't be focused
+    let focusable = |node: &Node, visibility: &InheritedVisibility| {
+        visibility.get() && node.size() != Vec2::ZERO
+    };
+    let current = focus
+        .0
+        .and_then(|entity| buttons.get(entity).ok())
+        .filter(|(_, node, _, visibility, _)| focusable(node, visibility))
+        .map(|(entity, _, transform, _, _)| (entity, transform.translation().truncate()));
+
+    let Some((current, position)) = current else {
+        // the first input only focuses the top button
+        let top = buttons
+            .iter()
+            .filter(|(_, node, _, visibility, _)| focusable(node, visibility))
+            .min_by(|(_, _, a, _, _), (_, _, b, _, _)| {
+                a.translation().y.total_cmp(&b.translation().y)
+            });
+        if let Some((entity, ..)) = top {
+            focus.0 = Some(entity);
+            commands.trigger(PlaySfx::new(Sfx::Key(SfxKey::ButtonHover)));
+        }
+        return;
+    };
+
+    if press {
+        if let Ok((.., mut interaction)) = buttons.get_mut(current) {
+            *interaction = Interaction::Pressed;
+            pressed.0 = Some(current);
+        }
+        return;
+    }
+    let Some(direction) = direction else {
+        return;
+    };
+
+    // left and right move a focused slider instead of the focus
+    if direction.y == 0. {
+        if let Ok(track) = slider_tracks.get(current) {
+            if let Ok(mut slider) = sliders.get_mut(track.get()) {
+                let value = (slider.value + direction.x * SLIDER_STEP).clamp(0., 1.);
+                if slider.value != value {
+                    slider.value = value;
+                    commands.trigger_targets(SliderChanged(value), track.get());
+                }
+                return;
+            }
+        }
+    }
+
+    // the closest button in that direction, preferring ones in line with the current one
+    let next = buttons
+        .iter()
+        .filter(|(entity, node, _, visibility, _)| {
+            *entity != current && focusable(node, visibility)
+        })
+        .filter_map(|(entity, _, transform, _, _)| {
+            let offset = transform.translation().truncate() - position;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (along > 1.).then_some((entity, along + 2. * across))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    if let Some((entity, _)) = next {
+        focus.0 = Some(entity);
+        commands.trigger(PlaySfx::new(Sfx::Key(SfxKey::ButtonHover)));
+    }
+}
@@ -1,12 +0,0 @@
-use bevy::prelude::*;
-
-// TODO get general Palette of the game from hannah before doing anything here.
-
-pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.186, 0.328, 0.573);
-pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
-
-pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
-pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
-pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
-
-pub const NODE_BACKGROUND: Color = Color::srgb(0., 0., 0.);
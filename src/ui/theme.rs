@@ -0,0 +1,324 @@
+//! The look of the UI, loaded from [`THEME_PATH`] so it can be reskinned without recompiling.
+//!
+//! Widgets are tagged with [`ThemedText`] or [`ThemedNode`] and restyled whenever
+//! the theme is loaded or hot-reloaded.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    color::HexColorError,
+    prelude::*,
+    ui::UiSystem,
+};
+use serde::Deserialize;
+
+use super::{interaction::InteractionPalette, navigation::Focus};
+use crate::settings::AccessibilitySettings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Theme>();
+    app.init_asset_loader::<ThemeLoader>();
+    app.init_resource::<UiTheme>();
+    app.register_type::<ThemedText>();
+    app.register_type::<ThemedNode>();
+    app.add_systems(PostUpdate, apply_theme.before(UiSystem::Layout));
+}
+
+pub const THEME_PATH: &str = "ui/default.theme.ron";
+
+/// The theme all widgets use.
+#[derive(Resource, Debug)]
+pub struct UiTheme(pub Handle<Theme>);
+
+impl FromWorld for UiTheme {
+    fn from_world(world: &mut World) -> Self {
+        Self(world.resource::<AssetServer>().load(THEME_PATH))
+    }
+}
+
+#[derive(Asset, TypePath, Debug)]
+pub struct Theme {
+    pub font: Handle<Font>,
+    pub font_sizes: ThemeFontSizes,
+    pub colors: ThemeColors,
+    pub corner_radii: ThemeCornerRadii,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct ThemeFontSizes {
+    pub button: f32,
+    pub header: f32,
+    pub label: f32,
+    /// The labels and values of sliders, toggles and other controls.
+    pub control: f32,
+    /// Multiplied by [`AccessibilitySettings::hud_scale`].
+    pub hud: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ThemeColors {
+    pub node_background: Color,
+    pub button_hovered: Color,
+    pub button_pressed: Color,
+    pub button_text: Color,
+    pub label_text: Color,
+    pub header_text: Color,
+    pub hud_text: Color,
+    pub panel_background: Color,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct ThemeCornerRadii {
+    pub button: f32,
+    pub header: f32,
+    pub panel: f32,
+    /// Sliders and progress bars.
+    pub bar: f32,
+}
+
+/// Which font size and color of the theme a text uses.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub enum ThemedText {
+    Button,
+    Header,
+    Label,
+    /// The label in front of a control.
+    ControlLabel,
+    /// The text on a control's button.
+    ControlValue,
+    Hud,
+}
+
+/// Which background color and corner radius of the theme a node uses.
+/// Nodes with an [`InteractionPalette`] get their palette from the theme as well.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub enum ThemedNode {
+    Button,
+    Header,
+    Panel,
+    /// The background of a slider or progress bar.
+    Bar,
+    /// The filled part of a slider or progress bar.
+    BarFill,
+}
+
+impl ThemedText {
+    fn style(&self, theme: &Theme, hud_scale: f32) -> TextStyle {
+        let sizes = &theme.font_sizes;
+        let colors = &theme.colors;
+        let (font_size, color) = match self {
+            ThemedText::Button => (sizes.button, colors.button_text),
+            ThemedText::Header => (sizes.header, colors.header_text),
+            ThemedText::Label => (sizes.label, colors.label_text),
+            ThemedText::ControlLabel => (sizes.control, colors.label_text),
+            ThemedText::ControlValue => (sizes.control, colors.button_text),
+            ThemedText::Hud => (sizes.hud * hud_scale, colors.hud_text),
+        };
+        TextStyle {
+            font: theme.font.clone(),
+            font_size,
+            color,
+        }
+    }
+}
+
+impl ThemedNode {
+    fn palette(&self, theme: &Theme) -> InteractionPalette {
+        let colors = &theme.colors;
+        match self {
+            ThemedNode::Button => InteractionPalette {
+                none: colors.node_background,
+                hovered: colors.button_hovered,
+                pressed: colors.button_pressed,
+            },
+            // dragging a slider doesn't make it look pressed
+            ThemedNode::Bar => InteractionPalette {
+                none: colors.node_background,
+                hovered: colors.button_hovered,
+                pressed: colors.button_hovered,
+            },
+            ThemedNode::Header => plain_palette(colors.node_background),
+            ThemedNode::Panel => plain_palette(colors.panel_background),
+            ThemedNode::BarFill => plain_palette(colors.button_pressed),
+        }
+    }
+
+    fn corner_radius(&self, theme: &Theme) -> f32 {
+        let radii = &theme.corner_radii;
+        match self {
+            ThemedNode::Button => radii.button,
+            ThemedNode::Header => radii.header,
+            ThemedNode::Panel => radii.panel,
+            ThemedNode::Bar | ThemedNode::BarFill => radii.bar,
+        }
+    }
+}
+
+/// The same color whatever the interaction, for nodes that aren't interactive.
+fn plain_palette(color: Color) -> InteractionPalette {
+    InteractionPalette {
+        none: color,
+        hovered: color,
+        pressed: color,
+    }
+}
+
+// runs before the layout, so new widgets never show up unstyled
+fn apply_theme(
+    mut theme_events: EventReader<AssetEvent<Theme>>,
+    ui_theme: Res<UiTheme>,
+    themes: Res<Assets<Theme>>,
+    settings: Res<AccessibilitySettings>,
+    focus: Res<Focus>,
+    mut texts: Query<(Ref<ThemedText>, &mut Text)>,
+    mut nodes: Query<(
+        Entity,
+        Ref<ThemedNode>,
+        &mut BackgroundColor,
+        &mut BorderRadius,
+        Option<(&Interaction, &mut InteractionPalette)>,
+    )>,
+) {
+    let theme_id = ui_theme.0.id();
+    let reloaded = theme_events.read().fold(false, |reloaded, event| {
+        reloaded || event.is_loaded_with_dependencies(theme_id) || event.is_modified(theme_id)
+    });
+    // until then widgets keep the default style, they are all restyled once it's loaded
+    let Some(theme) = themes.get(theme_id) else {
+        return;
+    };
+
+    for (themed, mut text) in &mut texts {
+        if !reloaded && !themed.is_added() {
+            continue;
+        }
+        let style = themed.style(theme, settings.hud_scale);
+        for section in &mut text.sections {
+            section.style = style.clone();
+        }
+    }
+
+    for (entity, themed, mut background, mut border_radius, interaction) in &mut nodes {
+        if !reloaded && !themed.is_added() {
+            continue;
+        }
+        let palette = themed.palette(theme);
+        *border_radius = BorderRadius::all(Val::Px(themed.corner_radius(theme)));
+        *background = match interaction {
+            Some((interaction, mut current_palette)) => {
+                let color = palette.color(*interaction, focus.0 == Some(entity));
+                *current_palette = palette;
+                color
+            }
+            None => palette.none,
+        }
+        .into();
+    }
+}
+
+#[derive(Default)]
+struct ThemeLoader;
+
+/// A [`Theme`] as it is written in the file.
+#[derive(Deserialize)]
+struct ThemeFile {
+    /// Path of the font, relative to the assets folder.
+    font: String,
+    font_sizes: ThemeFontSizes,
+    colors: ThemeColorsFile,
+    corner_radii: ThemeCornerRadii,
+}
+
+/// [`ThemeColors`] as hex strings like `"#2f5492"`.
+#[derive(Deserialize)]
+struct ThemeColorsFile {
+    node_background: String,
+    button_hovered: String,
+    button_pressed: String,
+    button_text: String,
+    label_text: String,
+    header_text: String,
+    hud_text: String,
+    panel_background: String,
+}
+
+impl ThemeColorsFile {
+    fn parse(&self) -> Result<ThemeColors, ThemeLoaderError> {
+        let color = |name: &'static str, hex: &str| {
+            Srgba::hex(hex)
+                .map(Color::from)
+                .map_err(|err| ThemeLoaderError::Color(name, err))
+        };
+        Ok(ThemeColors {
+            node_background: color("node_background", &self.node_background)?,
+            button_hovered: color("button_hovered", &self.button_hovered)?,
+            button_pressed: color("button_pressed", &self.button_pressed)?,
+            button_text: color("button_text", &self.button_text)?,
+            label_text: color("label_text", &self.label_text)?,
+            header_text: color("header_text", &self.header_text)?,
+            hud_text: color("hud_text", &self.hud_text)?,
+            panel_background: color("panel_background", &self.panel_background)?,
+        })
+    }
+}
+
+/// Why a theme file could not be loaded.
+#[derive(Debug)]
+pub enum ThemeLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    /// The color with the given name isn't a valid hex color.
+    Color(&'static str, HexColorError),
+}
+
+impl std::fmt::Display for ThemeLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeLoaderError::Io(err) => write!(f, "couldn't read the theme: {err}"),
+            ThemeLoaderError::Ron(err) => write!(f, "couldn't parse the theme: {err}"),
+            ThemeLoaderError::Color(name, err) => write!(f, "theme color {name}: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ThemeLoaderError {}
+
+impl From<std::io::Error> for ThemeLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        ThemeLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ThemeLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        ThemeLoaderError::Ron(err)
+    }
+}
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Theme, ThemeLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: ThemeFile = ron::de::from_bytes(&bytes)?;
+        Ok(Theme {
+            font: load_context.load(file.font),
+            font_sizes: file.font_sizes,
+            colors: file.colors.parse()?,
+            corner_radii: file.corner_radii,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
@@ -3,24 +3,56 @@
 use bevy::{ecs::system::EntityCommands, prelude::*, ui::Val::*};
 use bevy_aseprite_ultra::prelude::AsepriteAnimationUiBundle;
 
-use super::{interaction::InteractionPalette, palette::*};
+use super::{
+    controls::{
+        toggle_text, Dropdown, DropdownButton, DropdownOption, ProgressBar, Slider, SliderTrack,
+        TextInput, TextInputField, Toggle, ToggleButton,
+    },
+    interaction::InteractionPalette,
+    theme::{ThemedNode, ThemedText},
+};
 
 /// An extension trait for spawning UI widgets.
 pub trait Widgets {
     /// Spawn a simple button with text.
-    fn button(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn button(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
 
     /// Spawn a simple header label. Bigger than [`Widgets::label`].
-    fn header(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
 
     /// Spawn a simple text label.
-    fn label(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
 
-    fn icon(&mut self, texture: Handle<Image>) -> EntityCommands;
+    fn icon(&mut self, texture: Handle<Image>) -> EntityCommands<'_>;
+
+    /// Spawn a labeled [`Slider`] starting at `value`, which goes from 0 to 1.
+    fn slider(&mut self, text: impl Into<String>, value: f32) -> EntityCommands<'_>;
+
+    /// Spawn a labeled [`Toggle`] starting at `value`.
+    fn toggle(&mut self, text: impl Into<String>, value: bool) -> EntityCommands<'_>;
+
+    /// Spawn a labeled [`Dropdown`] with the option at index `selected` chosen.
+    fn dropdown(
+        &mut self,
+        text: impl Into<String>,
+        options: Vec<String>,
+        selected: usize,
+    ) -> EntityCommands<'_>;
+
+    /// Spawn a [`ProgressBar`] filled to `value`, which goes from 0 to 1.
+    fn progress_bar(&mut self, value: f32) -> EntityCommands<'_>;
+
+    /// Spawn a labeled [`TextInput`] holding `value`, accepting up to `max_len` characters.
+    fn text_input(
+        &mut self,
+        text: impl Into<String>,
+        value: impl Into<String>,
+        max_len: usize,
+    ) -> EntityCommands<'_>;
 }
 
 impl<T: Spawn> Widgets for T {
-    fn button(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn button(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
         let mut entity = self.spawn((
             Name::new("Button"),
             ButtonBundle {
@@ -31,33 +63,22 @@ impl<T: Spawn> Widgets for T {
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                border_radius: BorderRadius::all(Val::Px(10.)),
-                background_color: BackgroundColor(NODE_BACKGROUND),
                 ..default()
             },
-            InteractionPalette {
-                none: NODE_BACKGROUND,
-                hovered: BUTTON_HOVERED_BACKGROUND,
-                pressed: BUTTON_PRESSED_BACKGROUND,
-            },
+            InteractionPalette::default(),
+            ThemedNode::Button,
         ));
         entity.with_children(|children| {
             children.spawn((
                 Name::new("Button Text"),
-                TextBundle::from_section(
-                    text,
-                    TextStyle {
-                        font_size: 40.0,
-                        color: BUTTON_TEXT,
-                        ..default()
-                    },
-                ),
+                TextBundle::from_section(text, TextStyle::default()),
+                ThemedText::Button,
             ));
         });
         entity
     }
 
-    fn header(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
         let mut entity = self.spawn((
             Name::new("Header"),
             NodeBundle {
@@ -68,28 +89,21 @@ impl<T: Spawn> Widgets for T {
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                border_radius: BorderRadius::all(Val::Px(15.)),
-                background_color: BackgroundColor(NODE_BACKGROUND),
                 ..default()
             },
+            ThemedNode::Header,
         ));
         entity.with_children(|children| {
             children.spawn((
                 Name::new("Header Text"),
-                TextBundle::from_section(
-                    text,
-                    TextStyle {
-                        font_size: 40.0,
-                        color: HEADER_TEXT,
-                        ..default()
-                    },
-                ),
+                TextBundle::from_section(text, TextStyle::default()),
+                ThemedText::Header,
             ));
         });
         entity
     }
 
-    fn label(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
         let mut entity = self.spawn((
             Name::new("Label"),
             NodeBundle {
@@ -105,20 +119,14 @@ impl<T: Spawn> Widgets for T {
         entity.with_children(|children| {
             children.spawn((
                 Name::new("Label Text"),
-                TextBundle::from_section(
-                    text,
-                    TextStyle {
-                        font_size: 24.0,
-                        color: LABEL_TEXT,
-                        ..default()
-                    },
-                ),
+                TextBundle::from_section(text, TextStyle::default()),
+                ThemedText::Label,
             ));
         });
         entity
     }
 
-    fn icon(&mut self, texture: Handle<Image>) -> EntityCommands {
+    fn icon(&mut self, texture: Handle<Image>) -> EntityCommands<'_> {
         let entity = self.spawn((
             Name::new("Icon"),
             ImageBundle {
@@ -131,17 +139,290 @@ impl<T: Spawn> Widgets for T {
         ));
         entity
     }
+
+    fn slider(&mut self, text: impl Into<String>, value: f32) -> EntityCommands<'_> {
+        let mut entity = self.spawn((
+            Name::new("Slider"),
+            NodeBundle {
+                style: Style {
+                    width: Px(500.0),
+                    height: Px(40.0),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+        let mut fill = Entity::PLACEHOLDER;
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Slider Text"),
+                TextBundle::from_section(text, TextStyle::default()),
+                ThemedText::ControlLabel,
+            ));
+            children
+                .spawn((
+                    Name::new("Slider Track"),
+                    ButtonBundle {
+                        style: Style {
+                            width: Px(300.0),
+                            height: Px(20.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    InteractionPalette::default(),
+                    ThemedNode::Bar,
+                    SliderTrack,
+                ))
+                .with_children(|track| {
+                    fill = track
+                        .spawn((
+                            Name::new("Slider Fill"),
+                            NodeBundle {
+                                style: Style {
+                                    width: Percent(value * 100.),
+                                    height: Percent(100.),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            ThemedNode::BarFill,
+                        ))
+                        .id();
+                });
+        });
+        entity.insert(Slider { value, fill });
+        entity
+    }
+
+    fn toggle(&mut self, text: impl Into<String>, value: bool) -> EntityCommands<'_> {
+        let mut entity = self.spawn((Name::new("Toggle"), control_row()));
+        let mut value_text = Entity::PLACEHOLDER;
+        entity.with_children(|children| {
+            children.spawn((Name::new("Toggle Text"), control_label(text)));
+            children
+                .spawn((
+                    Name::new("Toggle Button"),
+                    control_button(),
+                    InteractionPalette::default(),
+                    ThemedNode::Button,
+                    ToggleButton,
+                ))
+                .with_children(|button| {
+                    value_text = button
+                        .spawn((
+                            Name::new("Toggle Value"),
+                            control_button_text(toggle_text(value)),
+                        ))
+                        .id();
+                });
+        });
+        entity.insert(Toggle {
+            value,
+            text: value_text,
+        });
+        entity
+    }
+
+    fn dropdown(
+        &mut self,
+        text: impl Into<String>,
+        options: Vec<String>,
+        selected: usize,
+    ) -> EntityCommands<'_> {
+        let mut entity = self.spawn((Name::new("Dropdown"), control_row()));
+        let dropdown = entity.id();
+        let mut selected_text = Entity::PLACEHOLDER;
+        let mut list = Entity::PLACEHOLDER;
+        entity.with_children(|children| {
+            children.spawn((Name::new("Dropdown Text"), control_label(text)));
+            children
+                .spawn((
+                    Name::new("Dropdown Button"),
+                    control_button(),
+                    InteractionPalette::default(),
+                    ThemedNode::Button,
+                    DropdownButton,
+                ))
+                .with_children(|button| {
+                    selected_text = button
+                        .spawn((
+                            Name::new("Dropdown Value"),
+                            control_button_text(options.get(selected).cloned().unwrap_or_default()),
+                        ))
+                        .id();
+                    // shown below the button, over whatever comes after the dropdown
+                    list = button
+                        .spawn((
+                            Name::new("Dropdown List"),
+                            NodeBundle {
+                                style: Style {
+                                    display: Display::None,
+                                    position_type: PositionType::Absolute,
+                                    top: Percent(100.),
+                                    left: Px(0.),
+                                    width: Percent(100.),
+                                    flex_direction: FlexDirection::Column,
+                                    ..default()
+                                },
+                                z_index: ZIndex::Global(10),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|list| {
+                            for (index, option) in options.iter().enumerate() {
+                                list.spawn((
+                                    Name::new("Dropdown Option"),
+                                    control_button(),
+                                    InteractionPalette::default(),
+                                    ThemedNode::Button,
+                                    DropdownOption { dropdown, index },
+                                ))
+                                .with_children(|option_button| {
+                                    option_button.spawn((
+                                        Name::new("Dropdown Option Text"),
+                                        control_button_text(option.clone()),
+                                    ));
+                                });
+                            }
+                        })
+                        .id();
+                });
+        });
+        entity.insert(Dropdown {
+            options,
+            selected,
+            open: false,
+            text: selected_text,
+            list,
+        });
+        entity
+    }
+
+    fn progress_bar(&mut self, value: f32) -> EntityCommands<'_> {
+        let mut entity = self.spawn((
+            Name::new("Progress Bar"),
+            NodeBundle {
+                style: Style {
+                    width: Px(500.0),
+                    height: Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ThemedNode::Bar,
+        ));
+        let mut fill = Entity::PLACEHOLDER;
+        entity.with_children(|children| {
+            fill = children
+                .spawn((
+                    Name::new("Progress Bar Fill"),
+                    NodeBundle {
+                        style: Style {
+                            width: Percent(value.clamp(0., 1.) * 100.),
+                            height: Percent(100.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    ThemedNode::BarFill,
+                ))
+                .id();
+        });
+        entity.insert(ProgressBar { value, fill });
+        entity
+    }
+
+    fn text_input(
+        &mut self,
+        text: impl Into<String>,
+        value: impl Into<String>,
+        max_len: usize,
+    ) -> EntityCommands<'_> {
+        let value = value.into();
+        let mut entity = self.spawn((Name::new("Text Input"), control_row()));
+        let mut value_text = Entity::PLACEHOLDER;
+        entity.with_children(|children| {
+            children.spawn((Name::new("Text Input Text"), control_label(text)));
+            children
+                .spawn((
+                    Name::new("Text Input Field"),
+                    control_button(),
+                    InteractionPalette::default(),
+                    ThemedNode::Button,
+                    TextInputField,
+                ))
+                .with_children(|field| {
+                    value_text = field
+                        .spawn((
+                            Name::new("Text Input Value"),
+                            control_button_text(value.clone()),
+                        ))
+                        .id();
+                });
+        });
+        entity.insert(TextInput {
+            value,
+            max_len,
+            focused: false,
+            text: value_text,
+        });
+        entity
+    }
+}
+
+/// A row with a label on the left and a control on the right.
+fn control_row() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            width: Px(500.0),
+            height: Px(40.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn control_label(text: impl Into<String>) -> (TextBundle, ThemedText) {
+    (
+        TextBundle::from_section(text, TextStyle::default()),
+        ThemedText::ControlLabel,
+    )
+}
+
+fn control_button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            width: Px(200.0),
+            height: Px(36.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn control_button_text(text: impl Into<String>) -> (TextBundle, ThemedText) {
+    (
+        TextBundle::from_section(text, TextStyle::default()),
+        ThemedText::ControlValue,
+    )
 }
 
 /// An extension trait for spawning UI containers.
 pub trait Containers {
     /// Spawns a root node that covers the full screen
     /// and centers its content horizontally and vertically.
-    fn ui_root(&mut self) -> EntityCommands;
+    fn ui_root(&mut self) -> EntityCommands<'_>;
 }
 
 impl Containers for Commands<'_, '_> {
-    fn ui_root(&mut self) -> EntityCommands {
+    fn ui_root(&mut self) -> EntityCommands<'_> {
         self.spawn((
             Name::new("UI Root"),
             NodeBundle {
@@ -166,17 +447,17 @@ impl Containers for Commands<'_, '_> {
 /// are able to spawn entities.
 /// Ideally, this trait should be [part of Bevy itself](https://github.com/bevyengine/bevy/issues/14231).
 trait Spawn {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands;
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_>;
 }
 
 impl Spawn for Commands<'_, '_> {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands {
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_> {
         self.spawn(bundle)
     }
 }
 
 impl Spawn for ChildBuilder<'_> {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands {
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_> {
         self.spawn(bundle)
     }
 }